@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Compares the bulk-fill initializers with initializing every element through a closure.
+//!
+//! The libtest bench harness is unstable, so the benchmarks are only built on nightly:
+//!
+//! ```sh
+//! cargo +nightly bench --bench fill
+//! ```
+
+#![cfg_attr(USE_RUSTC_FEATURES, feature(test))]
+#![cfg_attr(all(USE_RUSTC_FEATURES, feature = "alloc"), feature(allocator_api))]
+#![cfg(all(USE_RUSTC_FEATURES, any(feature = "std", feature = "alloc")))]
+
+extern crate test;
+
+use pin_init::*;
+use test::{black_box, Bencher};
+
+const LEN: usize = 1024 * 1024;
+
+#[bench]
+fn array_from_fn(b: &mut Bencher) {
+    b.iter(|| {
+        let value = black_box(0xa5u8);
+        let array: Box<[u8; LEN]> = Box::init(init_array_from_fn(|_| value)).unwrap();
+        black_box(array)
+    });
+}
+
+#[bench]
+fn array_repeat(b: &mut Bencher) {
+    b.iter(|| {
+        let value = black_box(0xa5u8);
+        let array: Box<[u8; LEN]> = Box::init(init_array_repeat(value)).unwrap();
+        black_box(array)
+    });
+}
+
+#[bench]
+fn array_copy_from_fn(b: &mut Bencher) {
+    let src: Box<[u32; LEN / 4]> = Box::init(init_array_from_fn(|i| i as u32)).unwrap();
+    b.iter(|| {
+        let src = black_box(&*src);
+        let array: Box<[u32; LEN / 4]> = Box::init(init_array_from_fn(|i| src[i])).unwrap();
+        black_box(array)
+    });
+}
+
+#[bench]
+fn array_copy_from(b: &mut Bencher) {
+    let src: Box<[u32; LEN / 4]> = Box::init(init_array_from_fn(|i| i as u32)).unwrap();
+    b.iter(|| {
+        let src = black_box(&*src);
+        let array: Box<[u32; LEN / 4]> = Box::init(init_array_copy_from(src)).unwrap();
+        black_box(array)
+    });
+}
+
+#[bench]
+#[cfg(feature = "dst")]
+fn slice_from_fn(b: &mut Bencher) {
+    b.iter(|| {
+        let value = black_box(u64::MAX);
+        let init = init_slice_from_fn(LEN / 8, |_| Ok::<_, core::convert::Infallible>(value));
+        let slice: Box<[u64]> = Box::init_dst(LEN / 8, init).unwrap();
+        black_box(slice)
+    });
+}
+
+#[bench]
+#[cfg(feature = "dst")]
+fn slice_fill(b: &mut Bencher) {
+    b.iter(|| {
+        let value = black_box(u64::MAX);
+        let slice: Box<[u64]> = Box::init_dst(LEN / 8, init_slice_fill(value, LEN / 8)).unwrap();
+        black_box(slice)
+    });
+}
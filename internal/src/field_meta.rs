@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Fields, Generics, Ident, Index, Member};
+
+/// Generates the `FieldMeta<source>` implementation used by the `debug-poison` feature.
+///
+/// The implementation is generated by `__field_meta!`, so it only exists when the feature is
+/// enabled.
+pub(crate) fn field_meta(
+    name: &Ident,
+    generics: &Generics,
+    fields: &Fields,
+    source: TokenStream,
+) -> TokenStream {
+    let (impl_gen, ty_gen, whr) = generics.split_for_impl();
+    let fields = fields.iter().enumerate().map(|(i, field)| {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+        let field_name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        };
+        let cfg_attrs = field.attrs.iter().filter(|a| a.path().is_ident("cfg"));
+        quote! {
+            #(#cfg_attrs)*
+            // SAFETY: The field is part of `*slot`, which is valid for reads and has been
+            // poisoned before being initialized.
+            if unsafe {
+                (&&::pin_init::__internal::FieldProbe::new(&raw const (*slot).#member))
+                    .__is_poisoned()
+            } {
+                f(#field_name);
+            }
+        }
+    });
+    quote! {
+        ::pin_init::__field_meta! {
+            impl [#impl_gen] #source for [#name #ty_gen] [#whr]
+            fn (slot, f) {
+                #[allow(unused_imports)]
+                use ::pin_init::__internal::{NoUninitProbe as _, UncheckedProbe as _};
+                #(#fields)*
+            }
+        }
+    }
+}
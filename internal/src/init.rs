@@ -1,14 +1,16 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    braced,
-    parse::{End, Parse},
+    braced, bracketed,
+    ext::IdentExt,
+    parse::{discouraged::Speculative, End, Parse, ParseStream},
     parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    token, Attribute, Block, Expr, ExprCall, ExprPath, Ident, Path, Token, Type,
+    token, Attribute, Block, Expr, ExprCall, ExprLit, ExprPath, ExprRange, Ident, Lit, Pat, Path,
+    RangeLimits, Token, Type,
 };
 
 use crate::diagnostics::{DiagCtxt, ErrorGuaranteed};
@@ -48,20 +50,131 @@ enum InitializerKind {
         _underscore_token: Token![_],
         _colon_token: Token![:],
         block: Block,
+        undo: Option<(Token![else], Block)>,
+    },
+    Nested {
+        ident: Ident,
+        _colon_token: Token![:],
+        path: Path,
+        _brace_token: token::Brace,
+        fields: Punctuated<InitializerField, Token![,]>,
+        rest: Option<(Token![..], Expr)>,
+    },
+    If {
+        _if_token: Token![if],
+        branches: Vec<(Expr, FieldBlock)>,
+        else_branch: FieldBlock,
+    },
+    Match {
+        _match_token: Token![match],
+        expr: Expr,
+        _brace_token: token::Brace,
+        arms: Vec<MatchArm>,
+    },
+    For {
+        _for_token: Token![for],
+        var: Ident,
+        _in_token: Token![in],
+        /// The length of the array if the range is written as `0..N`, `None` for `..`.
+        len: Option<Box<Expr>>,
+        _brace_token: token::Brace,
+        ident: Ident,
+        _left_arrow_token: Token![<-],
+        value: Box<Expr>,
     },
 }
 
 impl InitializerKind {
-    fn ident(&self) -> Option<&Ident> {
+    /// Whether this item ends with a block and thus does not need to be followed by a comma.
+    fn is_block_like(&self) -> bool {
+        matches!(
+            self,
+            Self::If { .. } | Self::Match { .. } | Self::For { .. }
+        )
+    }
+
+    /// The branches of an `if` or `match` item.
+    fn branches(&self) -> Option<Vec<&FieldBlock>> {
         match self {
-            Self::Value { ident, .. } | Self::Init { ident, .. } => Some(ident),
-            Self::Code { .. } => None,
+            Self::If {
+                branches,
+                else_branch,
+                ..
+            } => Some(
+                branches
+                    .iter()
+                    .map(|(_, block)| block)
+                    .chain([else_branch])
+                    .collect(),
+            ),
+            Self::Match { arms, .. } => Some(arms.iter().map(|arm| &arm.body).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// The fields initialized by one branch of an `if` or `match` item.
+struct FieldBlock {
+    brace_token: token::Brace,
+    fields: Punctuated<InitializerField, Token![,]>,
+}
+
+struct MatchArm {
+    pat: Pat,
+    guard: Option<(Token![if], Expr)>,
+    _fat_arrow_token: Token![=>],
+    body: FieldBlock,
+}
+
+/// Returns the fields that are initialized by `fields` together with their attributes and the span
+/// of their value.
+fn field_idents(
+    fields: &Punctuated<InitializerField, Token![,]>,
+) -> Vec<(&[Attribute], &Ident, Span)> {
+    let mut idents = vec![];
+    for InitializerField { attrs, kind } in fields {
+        match kind {
+            InitializerKind::Value { ident, value } => {
+                let span = value
+                    .as_ref()
+                    .map_or(ident.span(), |(_, value)| value.span());
+                idents.push((&attrs[..], ident, span));
+            }
+            InitializerKind::Init { ident, value, .. } => {
+                idents.push((&attrs[..], ident, value.span()))
+            }
+            InitializerKind::Nested {
+                ident,
+                _brace_token: brace_token,
+                ..
+            } => idents.push((&attrs[..], ident, brace_token.span.close())),
+            InitializerKind::For {
+                ident,
+                _brace_token: brace_token,
+                ..
+            } => idents.push((&attrs[..], ident, brace_token.span.close())),
+            InitializerKind::Code { .. } => {}
+            InitializerKind::If { .. } | InitializerKind::Match { .. } => {
+                // All branches initialize the same fields, which is checked by
+                // `init_branches`.
+                let branches = kind.branches().unwrap_or_default();
+                if let Some(first) = branches.first() {
+                    idents.extend(
+                        field_idents(&first.fields)
+                            .into_iter()
+                            .map(|(_, ident, span)| (&attrs[..], ident, span)),
+                    );
+                }
+            }
         }
     }
+    idents
 }
 
 enum InitializerAttribute {
     DefaultError(DefaultErrorAttribute),
+    FieldErrors,
+    Dst,
 }
 
 struct DefaultErrorAttribute {
@@ -85,7 +198,6 @@ pub(crate) fn expand(
     let error = error.map_or_else(
         || {
             if let Some(default_error) = attrs.iter().fold(None, |acc, attr| {
-                #[expect(irrefutable_let_patterns)]
                 if let InitializerAttribute::DefaultError(DefaultErrorAttribute { ty }) = attr {
                     Some(ty.clone())
                 } else {
@@ -102,6 +214,19 @@ pub(crate) fn expand(
         },
         |(_, err)| Box::new(err),
     );
+    // With `#[field_errors]`, the errors of field initializers are wrapped in `InitError` that
+    // records the name of the field.
+    let field_errors = attrs
+        .iter()
+        .any(|attr| matches!(attr, InitializerAttribute::FieldErrors));
+    let (error, field_error) = if field_errors {
+        (parse_quote!(::pin_init::InitError<#error>), Some(&*error))
+    } else {
+        (*error, None)
+    };
+    let is_dst = attrs
+        .iter()
+        .any(|attr| matches!(attr, InitializerAttribute::Dst));
     let slot = format_ident!("slot");
     let (has_data_trait, get_data, init_from_closure) = if pinned {
         (
@@ -116,23 +241,8 @@ pub(crate) fn expand(
             format_ident!("init_from_closure"),
         )
     };
-    let init_kind = get_init_kind(rest, dcx);
-    let zeroable_check = match init_kind {
-        InitKind::Normal => quote!(),
-        InitKind::Zeroing => quote! {
-            // The user specified `..Zeroable::zeroed()` at the end of the list of fields.
-            // Therefore we check if the struct implements `Zeroable` and then zero the memory.
-            // This allows us to also remove the check that all fields are present (since we
-            // already set the memory to zero and that is a valid bit pattern).
-            fn assert_zeroable<T: ?::core::marker::Sized>(_: *mut T)
-            where T: ::pin_init::Zeroable
-            {}
-            // Ensure that the struct is indeed `Zeroable`.
-            assert_zeroable(#slot);
-            // SAFETY: The type implements `Zeroable` by the check above.
-            unsafe { ::core::ptr::write_bytes(#slot, 0, 1) };
-        },
-    };
+    let init_kind = get_init_kind(rest.as_ref(), dcx);
+    let zeroable_check = make_zeroable_check(init_kind, &slot);
     let this = match this {
         None => quote!(),
         Some(This { ident, .. }) => quote! {
@@ -143,8 +253,23 @@ pub(crate) fn expand(
     };
     // `mixed_site` ensures that the data is not accessible to the user-controlled code.
     let data = Ident::new("__data", Span::mixed_site());
-    let init_fields = init_fields(&fields, pinned, &data, &slot);
-    let field_check = make_field_check(&fields, init_kind, &path);
+    let target = Target {
+        slot: &slot,
+        data: &data,
+        parent: None,
+        ancestors: &[],
+        is_dst,
+    };
+    let init_fields = init_fields(&fields, pinned, field_error, &target, dcx);
+    let field_check = make_field_check(
+        &fields,
+        init_kind,
+        &path,
+        brace_token,
+        &slot,
+        Some(&data),
+        is_dst,
+    );
     Ok(quote! {{
         // Get the data about fields from the supplied type.
         // SAFETY: TODO
@@ -173,16 +298,17 @@ pub(crate) fn expand(
     }})
 }
 
+#[derive(Clone, Copy)]
 enum InitKind {
     Normal,
     Zeroing,
 }
 
-fn get_init_kind(rest: Option<(Token![..], Expr)>, dcx: &mut DiagCtxt) -> InitKind {
+fn get_init_kind(rest: Option<&(Token![..], Expr)>, dcx: &mut DiagCtxt) -> InitKind {
     let Some((dotdot, expr)) = rest else {
         return InitKind::Normal;
     };
-    match &expr {
+    match expr {
         Expr::Call(ExprCall { func, args, .. }) if args.is_empty() => match &**func {
             Expr::Path(ExprPath {
                 attrs,
@@ -212,17 +338,94 @@ fn get_init_kind(rest: Option<(Token![..], Expr)>, dcx: &mut DiagCtxt) -> InitKi
     InitKind::Normal
 }
 
+/// Generate the code that zeroes the struct pointed to by `slot` if `..Zeroable::init_zeroed()` was
+/// given.
+fn make_zeroable_check(init_kind: InitKind, slot: &Ident) -> TokenStream {
+    match init_kind {
+        InitKind::Normal => quote!(),
+        InitKind::Zeroing => quote! {
+            // The user specified `..Zeroable::zeroed()` at the end of the list of fields.
+            // Therefore we check if the struct implements `Zeroable` and then zero the memory.
+            // This allows us to also remove the check that all fields are present (since we
+            // already set the memory to zero and that is a valid bit pattern).
+            fn assert_zeroable<T: ?::core::marker::Sized>(_: *mut T)
+            where T: ::pin_init::Zeroable
+            {}
+            // Ensure that the struct is indeed `Zeroable`.
+            assert_zeroable(#slot);
+            // SAFETY: The type implements `Zeroable` by the check above.
+            unsafe { ::core::ptr::write_bytes(#slot, 0, 1) };
+        },
+    }
+}
+
+/// The struct whose fields are initialized by [`init_fields`].
+struct Target<'a> {
+    /// Pointer to the struct.
+    slot: &'a Ident,
+    /// The projection data of the struct.
+    data: &'a Ident,
+    /// The `Slot` of the struct if it is a nested field of another struct.
+    parent: Option<&'a Ident>,
+    /// Pointers to the enclosing structs together with the name of the field containing the
+    /// struct, outermost first.
+    ancestors: &'a [(Ident, String)],
+    /// Whether the initializer has the `#[dst]` attribute, see [`make_field_check`].
+    is_dst: bool,
+}
+
 /// Generate the code that initializes the fields of the struct using the initializers in `field`.
 fn init_fields(
     fields: &Punctuated<InitializerField, Token![,]>,
     pinned: bool,
-    data: &Ident,
-    slot: &Ident,
+    field_error: Option<&Type>,
+    target: &Target<'_>,
+    dcx: &mut DiagCtxt,
 ) -> TokenStream {
+    let (res, guards) = init_fields_with_guards(fields, pinned, field_error, target, dcx);
+    let dismiss = guards.iter().map(|Guard { field, guard, cfgs }| {
+        if field.is_some() {
+            quote! {
+                #(#cfgs)*
+                ::core::mem::forget(#guard);
+            }
+        } else {
+            quote! {
+                #(#cfgs)*
+                #guard.dismiss();
+            }
+        }
+    });
+    quote! {
+        #res
+        // If execution reaches this point, all fields have been initialized. Therefore we can now
+        // dismiss the guards by forgetting them and drop the `else` blocks without running them.
+        #(#dismiss)*
+    }
+}
+
+/// The drop guard of an initialized field or of the `else` block of a code item.
+struct Guard {
+    /// The field owned by the guard, `None` for the guard of an `else` block.
+    field: Option<Ident>,
+    guard: Ident,
+    cfgs: Vec<Attribute>,
+}
+
+/// Generate the code that initializes the fields in `fields`, but keeps the drop guards alive.
+fn init_fields_with_guards(
+    fields: &Punctuated<InitializerField, Token![,]>,
+    pinned: bool,
+    field_error: Option<&Type>,
+    target: &Target<'_>,
+    dcx: &mut DiagCtxt,
+) -> (TokenStream, Vec<Guard>) {
+    let Target {
+        slot, data, parent, ..
+    } = *target;
     let mut guards = vec![];
-    let mut guard_attrs = vec![];
     let mut res = TokenStream::new();
-    for InitializerField { attrs, kind } in fields {
+    for (i, InitializerField { attrs, kind }) in fields.iter().enumerate() {
         let cfgs = {
             let mut cfgs = attrs.clone();
             cfgs.retain(|attr| attr.path().is_ident("cfg"));
@@ -230,9 +433,11 @@ fn init_fields(
         };
 
         let ident = match kind {
-            InitializerKind::Value { ident, .. } => ident,
-            InitializerKind::Init { ident, .. } => ident,
-            InitializerKind::Code { block, .. } => {
+            InitializerKind::Value { ident, .. }
+            | InitializerKind::Init { ident, .. }
+            | InitializerKind::Nested { ident, .. }
+            | InitializerKind::For { ident, .. } => ident,
+            InitializerKind::Code { block, undo, .. } => {
                 let stmt = &block.stmts;
                 res.extend(quote! {
                     #(#attrs)*
@@ -240,11 +445,45 @@ fn init_fields(
                         #(#stmt)*
                     }
                 });
+                if let Some((_, undo)) = undo {
+                    // `mixed_site` ensures that the guard is not accessible to the user-controlled
+                    // code.
+                    let guard = format_ident!("__undo{i}_guard", span = Span::mixed_site());
+                    let undo = &undo.stmts;
+                    res.extend(quote! {
+                        #(#cfgs)*
+                        let #guard = ::pin_init::__internal::UndoGuard::new(|| {
+                            #(#undo)*
+                        });
+                    });
+                    guards.push(Guard {
+                        field: None,
+                        guard,
+                        cfgs,
+                    });
+                }
+                continue;
+            }
+            InitializerKind::If { .. } | InitializerKind::Match { .. } => {
+                let (init, branch_guards) =
+                    init_branches(kind, attrs, &cfgs, pinned, field_error, target, dcx);
+                res.extend(init);
+                guards.extend(branch_guards);
                 continue;
             }
         };
 
-        let slot = if pinned {
+        let slot = if let (true, Some(parent)) = (pinned, parent) {
+            quote! {
+                // SAFETY:
+                // - `slot` is valid and properly aligned.
+                // - `make_field_check` checks that `&raw mut (*slot).#ident` is properly aligned.
+                // - `make_field_check` prevents `#ident` from being used twice, therefore
+                //   `(*slot).#ident` is exclusively accessed and has not been initialized.
+                // - `(*slot).#ident` is a field of `*#parent.as_ptr()`.
+                (unsafe { #parent.project(#data.#ident(#slot)) })
+            }
+        } else if pinned {
             quote! {
                 // SAFETY:
                 // - `slot` is valid and properly aligned.
@@ -286,16 +525,125 @@ fn init_fields(
                 }
             }
             InitializerKind::Init { value, .. } => {
+                let init = init_field(
+                    attrs,
+                    ident,
+                    &guard,
+                    quote!(#slot.init(#value)),
+                    false,
+                    field_error,
+                    target,
+                );
+                let field_slot = target.slot;
+                quote! {
+                    #(#cfgs)*
+                    ::pin_init::__debug_poison! {
+                        // SAFETY: `make_field_check` prevents `#ident` from being used twice,
+                        // therefore `(*slot).#ident` is valid for writes and has not been
+                        // initialized.
+                        unsafe {
+                            ::pin_init::__internal::poison_field(&raw mut (*#field_slot).#ident)
+                        };
+                    }
+                    #init
+                    #(#cfgs)*
+                    ::pin_init::__debug_poison! {
+                        // SAFETY: `(*slot).#ident` has been poisoned above and then initialized.
+                        unsafe {
+                            ::pin_init::__internal::check_field(&raw const (*#field_slot).#ident)
+                        };
+                    }
+                }
+            }
+            InitializerKind::For {
+                var, len, value, ..
+            } => {
+                // With `#[field_errors]`, the index of the failing element is returned with the
+                // error.
+                let value = if field_error.is_some() {
+                    quote!(::pin_init::__internal::Indexed(#var, #value))
+                } else {
+                    quote!(#value)
+                };
+                // The length is a const generic argument, so a range that does not cover the whole
+                // array does not type check.
+                let len = len.as_ref().map(|len| quote!(::<_, _, { #len }>));
+                let init = quote! {
+                    #slot.init(::pin_init::__internal::array_for #len (|#var| #value))
+                };
+                let indexed = field_error.is_some();
+                init_field(attrs, ident, &guard, init, indexed, field_error, target)
+            }
+            InitializerKind::Nested {
+                path,
+                _brace_token: brace_token,
+                fields,
+                rest,
+                ..
+            } => {
+                let depth = target.ancestors.len() + 1;
+                let nested_slot = format_ident!("__slot{depth}", span = Span::mixed_site());
+                let nested_ptr = format_ident!("__ptr{depth}", span = Span::mixed_site());
+                let nested_data = format_ident!("__data{depth}", span = Span::mixed_site());
+                let get_data = pinned.then(|| {
+                    quote! {
+                        // SAFETY: TODO
+                        let #nested_data = unsafe {
+                            use ::pin_init::__internal::HasPinData;
+                            #path::__pin_data()
+                        };
+                    }
+                });
+                let mut ancestors = target.ancestors.to_vec();
+                ancestors.push((target.slot.clone(), ident.to_string()));
+                let nested = Target {
+                    slot: &nested_ptr,
+                    data: &nested_data,
+                    parent: Some(&nested_slot),
+                    ancestors: &ancestors,
+                    is_dst: target.is_dst,
+                };
+                let init_kind = get_init_kind(rest.as_ref(), dcx);
+                let zeroable_check = make_zeroable_check(init_kind, &nested_ptr);
+                let init_fields = init_fields(fields, pinned, field_error, &nested, dcx);
+                let field_check = make_field_check(
+                    fields,
+                    init_kind,
+                    path,
+                    *brace_token,
+                    &nested_ptr,
+                    pinned.then_some(&nested_data),
+                    target.is_dst,
+                );
                 quote! {
                     #(#attrs)*
-                    let mut #guard = #slot.init(#value)?;
+                    let mut #guard = {
+                        let #nested_slot = #slot;
+                        let #nested_ptr = #nested_slot.as_ptr();
+                        #get_data
+                        #zeroable_check
+                        #init_fields
+                        #field_check
+                        // SAFETY: All fields of `*#nested_ptr` have been initialized above.
+                        unsafe { #nested_slot.assume_init() }
+                    };
                 }
             }
-            InitializerKind::Code { .. } => unreachable!(),
+            InitializerKind::Code { .. }
+            | InitializerKind::If { .. }
+            | InitializerKind::Match { .. } => unreachable!(),
         };
 
+        // `mixed_site` ensures that the span is not accessible to the user-controlled code.
+        let span = format_ident!("__{ident}_span", span = Span::mixed_site());
+        let field_slot = target.slot;
+        let field_name = ident.unraw().to_string();
         res.extend(quote! {
+            #(#cfgs)*
+            let #span = ::pin_init::__internal::FieldSpan::start(#field_slot, #field_name);
             #init
+            #(#cfgs)*
+            #span.success(&mut #guard);
 
             #(#cfgs)*
             // Allow `non_snake_case` since the same warning is going to be reported for the struct
@@ -304,18 +652,151 @@ fn init_fields(
             let #ident = #guard.let_binding();
         });
 
-        guards.push(guard);
-        guard_attrs.push(cfgs);
+        guards.push(Guard {
+            field: Some(ident.clone()),
+            guard,
+            cfgs,
+        });
+    }
+    (res, guards)
+}
+
+/// Generate the code that initializes a field with `init`, which evaluates to the result of
+/// `Slot::init`. If `indexed`, the error of a failing element of an array is returned with its
+/// index.
+fn init_field(
+    attrs: &[Attribute],
+    ident: &Ident,
+    guard: &Ident,
+    init: TokenStream,
+    indexed: bool,
+    field_error: Option<&Type>,
+    target: &Target<'_>,
+) -> TokenStream {
+    let Some(error) = field_error else {
+        return quote! {
+            #(#attrs)*
+            let mut #guard = #init?;
+        };
+    };
+    // `mixed_site` ensures that the index is not accessible to the user-controlled code.
+    let index = Ident::new("__index", Span::mixed_site());
+    let (pattern, mut index) = if indexed {
+        (
+            quote!((#index, err)),
+            quote!(::core::option::Option::Some(#index)),
+        )
+    } else {
+        (quote!(err), quote!(::core::option::Option::None))
+    };
+    // Convert the error of the field initializer, keeping its path if it already has one. See
+    // `__internal::NestedFieldError` for how the conversion is chosen.
+    let mut err = quote! {{
+        #[allow(unused_imports)]
+        use ::pin_init::__internal::{NestedFieldError as _, SourceFieldError as _};
+        (&err).__field_error_kind().convert::<#error, _>(err)
+    }};
+    // Record the path of the field, starting at the innermost struct.
+    for (slot, field) in target
+        .ancestors
+        .iter()
+        .map(|(slot, field)| (slot, field.clone()))
+        .chain([(target.slot, ident.to_string())])
+        .rev()
+    {
+        err = quote! {
+            ::pin_init::__internal::field_error::<_, #error>(#slot, #field, #index, #err)
+        };
+        index = quote!(::core::option::Option::None);
     }
     quote! {
-        #res
-        // If execution reaches this point, all fields have been initialized. Therefore we can now
-        // dismiss the guards by forgetting them.
-        #(
-            #(#guard_attrs)*
-            ::core::mem::forget(#guards);
-        )*
+        #(#attrs)*
+        let mut #guard = match #init {
+            ::core::result::Result::Ok(guard) => guard,
+            ::core::result::Result::Err(#pattern) => {
+                return ::core::result::Result::Err(#err);
+            }
+        };
+    }
+}
+
+/// Generate the code for an `if` or `match` item. Every branch initializes the same fields and
+/// evaluates to the tuple of their drop guards.
+fn init_branches(
+    kind: &InitializerKind,
+    attrs: &[Attribute],
+    cfgs: &[Attribute],
+    pinned: bool,
+    field_error: Option<&Type>,
+    target: &Target<'_>,
+    dcx: &mut DiagCtxt,
+) -> (TokenStream, Vec<Guard>) {
+    let blocks = kind.branches().unwrap_or_default();
+    let mut expected: Option<Vec<Guard>> = None;
+    let mut bodies = vec![];
+    for block in blocks {
+        // The parser ensures that all branches initialize the same fields.
+        let (res, guards) =
+            init_fields_with_guards(&block.fields, pinned, field_error, target, dcx);
+        let expected = expected.get_or_insert_with(|| {
+            guards
+                .into_iter()
+                .map(|guard| Guard {
+                    cfgs: cfgs.to_vec(),
+                    ..guard
+                })
+                .collect()
+        });
+        let expected = expected.iter().map(|guard| &guard.guard);
+        bodies.push(quote! {{
+            #res
+            (#(#expected,)*)
+        }});
     }
+    let expected = expected.unwrap_or_default();
+    let control_flow = match kind {
+        InitializerKind::If { branches, .. } => {
+            let conds = branches.iter().map(|(cond, _)| cond);
+            let (else_body, bodies) = bodies.split_last().expect("`if` has an `else` branch");
+            quote! {
+                #(if #conds #bodies) else *
+                else #else_body
+            }
+        }
+        InitializerKind::Match { expr, arms, .. } => {
+            let pats = arms.iter().map(|arm| &arm.pat);
+            let guards = arms.iter().map(|arm| {
+                arm.guard
+                    .as_ref()
+                    .map(|(if_token, guard)| quote!(#if_token #guard))
+            });
+            quote! {
+                match #expr {
+                    #(#pats #guards => #bodies,)*
+                }
+            }
+        }
+        _ => unreachable!(),
+    };
+    let guards = expected.iter().map(|guard| &guard.guard);
+    let bindings = expected.iter().map(|guard| {
+        let Guard { field, guard, .. } = guard;
+        // `check_branches` rejects `else` blocks of code items inside of branches.
+        let field = field.as_ref().expect("branches only contain field guards");
+        quote! {
+            #(#cfgs)*
+            // Allow `non_snake_case` since the same warning is going to be reported for the struct
+            // field.
+            #[allow(unused_variables, non_snake_case)]
+            let #field = #guard.let_binding();
+        }
+    });
+    let res = quote! {
+        #(#attrs)*
+        let (#(mut #guards,)*) = #control_flow;
+        #(#bindings)*
+    };
+    (res, expected)
 }
 
 /// Generate the check for ensuring that every field has been initialized and aligned.
@@ -323,17 +804,59 @@ fn make_field_check(
     fields: &Punctuated<InitializerField, Token![,]>,
     init_kind: InitKind,
     path: &Path,
+    brace_token: token::Brace,
+    slot: &Ident,
+    data: Option<&Ident>,
+    is_dst: bool,
 ) -> TokenStream {
-    let field_attrs: Vec<_> = fields
+    let fields = field_idents(fields);
+    let (field_attrs, field_name): (Vec<_>, Vec<_>) = fields
         .iter()
-        .filter_map(|f| f.kind.ident().map(|_| &f.attrs))
-        .collect();
-    let field_name: Vec<_> = fields.iter().filter_map(|f| f.kind.ident()).collect();
-    let zeroing_trailer = match init_kind {
-        InitKind::Normal => None,
-        InitKind::Zeroing => Some(quote! {
-            ..::core::mem::zeroed()
-        }),
+        .map(|(attrs, name, _)| (*attrs, *name))
+        .unzip();
+    // The data converts the pointer for structs annotated with `#[pin_data(remote = ...)]`, whose
+    // initializers write to the remote struct.
+    let checked_slot = match data {
+        Some(data) => quote!(#data.__field_check_slot(#slot)),
+        None => quote!(#slot),
+    };
+    let check = if is_dst {
+        let mut pattern = quote_spanned!(path.span()=> #path);
+        brace_token.surround(&mut pattern, |pattern| {
+            for (attrs, name, span) in &fields {
+                // The `_` gets the span of the value, so suggestions for missing fields keep the
+                // fields that are already present intact.
+                let wildcard = quote_spanned!(*span=> _);
+                pattern.extend(quote!(#(#attrs)* #name: #wildcard,));
+            }
+            if let InitKind::Zeroing = init_kind {
+                pattern.extend(quote!(..));
+            }
+        });
+        // A struct expression requires the struct to be `Sized`, which is not the case for
+        // structs with a trailing slice, so we use a struct pattern instead.
+        quote! {
+            #[allow(clippy::unneeded_wildcard_pattern)]
+            let #pattern = &*#checked_slot;
+        }
+    } else {
+        let zeroing_trailer = match init_kind {
+            InitKind::Normal => None,
+            InitKind::Zeroing => Some(quote! {
+                ..::core::mem::zeroed()
+            }),
+        };
+        // The use of struct initializer will still generate very natural error messages for any
+        // misuse.
+        quote! {
+            ::core::ptr::write(#checked_slot, #path {
+                #(
+                    #(#field_attrs)*
+                    #field_name: loop {},
+                )*
+                #zeroing_trailer
+            })
+        }
     };
     quote! {
         #[allow(unreachable_code)]
@@ -346,20 +869,13 @@ fn make_field_check(
             // `ptr::write` for value-initialization case has the same requirement.
             #(
                 #(#field_attrs)*
-                let _ = &(*slot).#field_name;
+                let _ = &(*#slot).#field_name;
             )*
 
             // If the zeroing trailer is not present, this checks that all fields have been
             // mentioned exactly once. If the zeroing trailer is present, all missing fields will be
-            // zeroed, so this checks that all fields have been mentioned at most once. The use of
-            // struct initializer will still generate very natural error messages for any misuse.
-            ::core::ptr::write(slot, #path {
-                #(
-                    #(#field_attrs)*
-                    #field_name: loop {},
-                )*
-                #zeroing_trailer
-            })
+            // zeroed, so this checks that all fields have been mentioned at most once.
+            #check
         };
     }
 }
@@ -371,29 +887,7 @@ impl Parse for Initializer {
         let path = input.parse()?;
         let content;
         let brace_token = braced!(content in input);
-        let mut fields = Punctuated::new();
-        loop {
-            let lh = content.lookahead1();
-            if lh.peek(End) || lh.peek(Token![..]) {
-                break;
-            } else if lh.peek(Ident) || lh.peek(Token![_]) || lh.peek(Token![#]) {
-                fields.push_value(content.parse()?);
-                let lh = content.lookahead1();
-                if lh.peek(End) {
-                    break;
-                } else if lh.peek(Token![,]) {
-                    fields.push_punct(content.parse()?);
-                } else {
-                    return Err(lh.error());
-                }
-            } else {
-                return Err(lh.error());
-            }
-        }
-        let rest = content
-            .peek(Token![..])
-            .then(|| Ok::<_, syn::Error>((content.parse()?, content.parse()?)))
-            .transpose()?;
+        let (fields, rest) = parse_fields(&content)?;
         let error = input
             .peek(Token![?])
             .then(|| Ok::<_, syn::Error>((input.parse()?, input.parse()?)))
@@ -404,6 +898,14 @@ impl Parse for Initializer {
                 if a.path().is_ident("default_error") {
                     a.parse_args::<DefaultErrorAttribute>()
                         .map(InitializerAttribute::DefaultError)
+                } else if a.path().is_ident("field_errors") {
+                    a.meta
+                        .require_path_only()
+                        .map(|_| InitializerAttribute::FieldErrors)
+                } else if a.path().is_ident("dst") {
+                    a.meta
+                        .require_path_only()
+                        .map(|_| InitializerAttribute::Dst)
                 } else {
                     Err(syn::Error::new_spanned(a, "unknown initializer attribute"))
                 }
@@ -421,6 +923,51 @@ impl Parse for Initializer {
     }
 }
 
+/// Parses the contents of the braces of an initializer.
+#[expect(clippy::type_complexity)]
+fn parse_fields(
+    content: ParseStream<'_>,
+) -> syn::Result<(
+    Punctuated<InitializerField, Token![,]>,
+    Option<(Token![..], Expr)>,
+)> {
+    let mut fields = Punctuated::new();
+    loop {
+        let lh = content.lookahead1();
+        if lh.peek(End) || lh.peek(Token![..]) {
+            break;
+        } else if lh.peek(Ident)
+            || lh.peek(Token![_])
+            || lh.peek(Token![#])
+            || lh.peek(Token![if])
+            || lh.peek(Token![match])
+            || lh.peek(Token![for])
+        {
+            let field: InitializerField = content.parse()?;
+            let block_like = field.kind.is_block_like();
+            fields.push_value(field);
+            let lh = content.lookahead1();
+            if lh.peek(End) {
+                break;
+            } else if lh.peek(Token![,]) {
+                fields.push_punct(content.parse()?);
+            } else if block_like {
+                // Like statements, items ending with a block do not need a trailing comma.
+                fields.push_punct(Token![,](Span::call_site()));
+            } else {
+                return Err(lh.error());
+            }
+        } else {
+            return Err(lh.error());
+        }
+    }
+    let rest = content
+        .peek(Token![..])
+        .then(|| Ok::<_, syn::Error>((content.parse()?, content.parse()?)))
+        .transpose()?;
+    Ok((fields, rest))
+}
+
 impl Parse for DefaultErrorAttribute {
     fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
         Ok(Self { ty: input.parse()? })
@@ -450,11 +997,22 @@ impl Parse for InitializerField {
 impl Parse for InitializerKind {
     fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
         let lh = input.lookahead1();
-        if lh.peek(Token![_]) {
+        if lh.peek(Token![if]) {
+            Self::parse_if(input)
+        } else if lh.peek(Token![match]) {
+            Self::parse_match(input)
+        } else if lh.peek(Token![for]) {
+            Self::parse_for(input)
+        } else if lh.peek(Token![_]) {
             Ok(Self::Code {
                 _underscore_token: input.parse()?,
                 _colon_token: input.parse()?,
                 block: input.parse()?,
+                undo: if input.peek(Token![else]) {
+                    Some((input.parse()?, input.parse()?))
+                } else {
+                    None
+                },
             })
         } else if lh.peek(Ident) {
             let ident = input.parse()?;
@@ -466,9 +1024,13 @@ impl Parse for InitializerKind {
                     value: input.parse()?,
                 })
             } else if lh.peek(Token![:]) {
+                let colon_token = input.parse()?;
+                if let Some(nested) = Self::parse_nested(ident.clone(), colon_token, input) {
+                    return Ok(nested);
+                }
                 Ok(Self::Value {
                     ident,
-                    value: Some((input.parse()?, input.parse()?)),
+                    value: Some((colon_token, input.parse()?)),
                 })
             } else if lh.peek(Token![,]) || lh.peek(End) {
                 Ok(Self::Value { ident, value: None })
@@ -480,3 +1042,227 @@ impl Parse for InitializerKind {
         }
     }
 }
+
+impl InitializerKind {
+    /// Tries to parse `Path { ... }` as a nested initializer.
+    ///
+    /// This only succeeds if the braces contain initializer syntax (`<-`, `_:` or another nested
+    /// initializer), otherwise it is parsed as a normal struct expression.
+    fn parse_nested(ident: Ident, colon_token: Token![:], input: ParseStream<'_>) -> Option<Self> {
+        let fork = input.fork();
+        let parse = |input: ParseStream<'_>| {
+            let path = input.parse()?;
+            let content;
+            let brace_token = braced!(content in input);
+            let (fields, rest) = parse_fields(&content)?;
+            Ok::<_, syn::Error>((path, brace_token, fields, rest))
+        };
+        let (path, brace_token, fields, rest) = parse(&fork).ok()?;
+        if !(fork.is_empty() || fork.peek(Token![,]))
+            || fields
+                .iter()
+                .all(|field| matches!(field.kind, Self::Value { .. }))
+        {
+            return None;
+        }
+        input.advance_to(&fork);
+        Some(Self::Nested {
+            ident,
+            _colon_token: colon_token,
+            path,
+            _brace_token: brace_token,
+            fields,
+            rest,
+        })
+    }
+}
+
+impl InitializerKind {
+    fn parse_if(input: ParseStream<'_>) -> syn::Result<Self> {
+        let if_token: Token![if] = input.parse()?;
+        let mut branches = vec![(Expr::parse_without_eager_brace(input)?, input.parse()?)];
+        loop {
+            if !input.peek(Token![else]) {
+                return Err(syn::Error::new(
+                    if_token.span,
+                    "expected an `else` branch, every branch has to initialize the same fields",
+                ));
+            }
+            input.parse::<Token![else]>()?;
+            if input.peek(Token![if]) {
+                input.parse::<Token![if]>()?;
+                branches.push((Expr::parse_without_eager_brace(input)?, input.parse()?));
+            } else {
+                let this = Self::If {
+                    _if_token: if_token,
+                    branches,
+                    else_branch: input.parse()?,
+                };
+                this.check_branches()?;
+                return Ok(this);
+            }
+        }
+    }
+
+    fn parse_match(input: ParseStream<'_>) -> syn::Result<Self> {
+        let match_token = input.parse()?;
+        let expr = Expr::parse_without_eager_brace(input)?;
+        let content;
+        let brace_token = braced!(content in input);
+        let mut arms = vec![];
+        while !content.is_empty() {
+            arms.push(MatchArm {
+                pat: Pat::parse_multi_with_leading_vert(&content)?,
+                guard: content
+                    .peek(Token![if])
+                    .then(|| Ok::<_, syn::Error>((content.parse()?, content.parse()?)))
+                    .transpose()?,
+                _fat_arrow_token: content.parse()?,
+                body: content.parse()?,
+            });
+            if !content.is_empty() {
+                content.parse::<Option<Token![,]>>()?;
+            }
+        }
+        let this = Self::Match {
+            _match_token: match_token,
+            expr,
+            _brace_token: brace_token,
+            arms,
+        };
+        this.check_branches()?;
+        Ok(this)
+    }
+
+    /// Checks that every branch initializes the same fields and contains no `else` blocks of code
+    /// items.
+    fn check_branches(&self) -> syn::Result<()> {
+        let branches = self.branches().unwrap_or_default();
+        let names = |block: &FieldBlock| {
+            let mut names: Vec<_> = field_idents(&block.fields)
+                .into_iter()
+                .map(|(_, ident, _)| ident.to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        for block in &branches {
+            for field in &block.fields {
+                if let InitializerKind::Code {
+                    undo: Some((else_token, _)),
+                    ..
+                } = &field.kind
+                {
+                    return Err(syn::Error::new_spanned(
+                        else_token,
+                        "`else` blocks of code items are not supported inside of branches",
+                    ));
+                }
+            }
+        }
+        let Some((first, rest)) = branches.split_first() else {
+            return Ok(());
+        };
+        let expected = names(first);
+        for block in rest {
+            if names(block) != expected {
+                return Err(syn::Error::new(
+                    block.brace_token.span.join(),
+                    format!(
+                        "every branch has to initialize the same fields: `{}`",
+                        expected.join("`, `")
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_for(input: ParseStream<'_>) -> syn::Result<Self> {
+        let for_token = input.parse()?;
+        let var: Ident = input.parse()?;
+        let in_token = input.parse()?;
+        let range = Expr::parse_without_eager_brace(input)?;
+        let len = match range {
+            Expr::Range(ExprRange {
+                attrs,
+                start: None,
+                limits: RangeLimits::HalfOpen(_),
+                end: None,
+            }) if attrs.is_empty() => None,
+            Expr::Range(ExprRange {
+                attrs,
+                start: Some(start),
+                limits: RangeLimits::HalfOpen(_),
+                end: Some(end),
+            }) if attrs.is_empty()
+                && matches!(&*start, Expr::Lit(ExprLit { lit: Lit::Int(lit), attrs })
+                    if attrs.is_empty() && lit.base10_digits() == "0") =>
+            {
+                Some(end)
+            }
+            range => {
+                return Err(syn::Error::new_spanned(
+                    range,
+                    "the range of a `for` loop has to be `..` or `0..N`, where `N` is the length \
+                     of the array",
+                ));
+            }
+        };
+        let content;
+        let brace_token = braced!(content in input);
+        let ident = content.parse()?;
+        let index_content;
+        bracketed!(index_content in content);
+        let index: Expr = index_content.parse()?;
+        if !matches!(&index, Expr::Path(path) if path.attrs.is_empty() && path.path.is_ident(&var))
+        {
+            return Err(syn::Error::new_spanned(
+                index,
+                format!("expected the loop variable `{var}` as the index"),
+            ));
+        }
+        let left_arrow_token = content.parse()?;
+        let value = content.parse()?;
+        content.parse::<Option<Token![,]>>()?;
+        if !content.is_empty() {
+            return Err(content.error("a `for` loop can only initialize a single array field"));
+        }
+        Ok(Self::For {
+            _for_token: for_token,
+            var,
+            _in_token: in_token,
+            len,
+            _brace_token: brace_token,
+            ident,
+            _left_arrow_token: left_arrow_token,
+            value,
+        })
+    }
+}
+
+impl Parse for FieldBlock {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let content;
+        let brace_token = braced!(content in input);
+        let (fields, rest) = parse_fields(&content)?;
+        if let Some((dotdot, _)) = rest {
+            return Err(syn::Error::new_spanned(
+                dotdot,
+                "`..` is not supported inside of branches",
+            ));
+        }
+        for attr in fields.iter().flat_map(|field| &field.attrs) {
+            if attr.path().is_ident("cfg") {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`#[cfg]` is not supported inside of branches",
+                ));
+            }
+        }
+        Ok(Self {
+            brace_token,
+            fields,
+        })
+    }
+}
@@ -15,9 +15,11 @@ use syn::parse_macro_input;
 use crate::diagnostics::DiagCtxt;
 
 mod diagnostics;
+mod field_meta;
 mod init;
 mod pin_data;
 mod pinned_drop;
+mod select;
 mod zeroable;
 
 #[proc_macro_attribute]
@@ -57,3 +59,9 @@ pub fn pin_init(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input);
     DiagCtxt::with(|dcx| init::expand(input, Some("::core::convert::Infallible"), true, dcx)).into()
 }
+
+#[proc_macro]
+pub fn select_init(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+    DiagCtxt::with(|dcx| select::expand(input, dcx)).into()
+}
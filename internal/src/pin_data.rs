@@ -1,34 +1,112 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse::{End, Nothing, Parse},
+    parse::{End, Parse},
     parse_quote, parse_quote_spanned,
     spanned::Spanned,
     visit_mut::VisitMut,
-    Attribute, Field, Generics, Ident, Item, PathSegment, Type, TypePath, Visibility, WhereClause,
+    Attribute, Expr, Field, GenericParam, Generics, Ident, Item, Path, PathArguments, PathSegment,
+    Token, Type, TypePath, Visibility, WhereClause,
 };
 
-use crate::diagnostics::{DiagCtxt, ErrorGuaranteed};
+use crate::{
+    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    field_meta,
+};
 
 pub(crate) mod kw {
     syn::custom_keyword!(PinnedDrop);
+    syn::custom_keyword!(new);
+    syn::custom_keyword!(builder);
+    syn::custom_keyword!(remote);
+    syn::custom_keyword!(value);
+    syn::custom_keyword!(default);
 }
 
-pub(crate) enum Args {
-    Nothing(Nothing),
-    #[allow(dead_code)]
-    PinnedDrop(kw::PinnedDrop),
+/// The comma separated arguments of `#[pin_data(...)]`.
+#[derive(Default)]
+pub(crate) struct Args {
+    pinned_drop: Option<kw::PinnedDrop>,
+    new: Option<kw::new>,
+    builder: Option<kw::builder>,
+    /// The struct mirrored by the annotated struct, given by `unsafe remote = path::Foo`.
+    remote: Option<Path>,
 }
 
 impl Parse for Args {
+    fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let lh = input.lookahead1();
+            if lh.peek(kw::PinnedDrop) {
+                set_once(&mut args.pinned_drop, input.parse()?, "PinnedDrop")?;
+            } else if lh.peek(kw::new) {
+                set_once(&mut args.new, input.parse()?, "new")?;
+            } else if lh.peek(kw::builder) {
+                set_once(&mut args.builder, input.parse()?, "builder")?;
+            } else if lh.peek(Token![unsafe]) {
+                input.parse::<Token![unsafe]>()?;
+                input.parse::<kw::remote>()?;
+                input.parse::<Token![=]>()?;
+                set_once(&mut args.remote, input.parse()?, "remote")?;
+            } else if input.peek(kw::remote) {
+                // The remote crate has to uphold the structural pinning declared by the mirror.
+                return Err(
+                    input.error("`remote` must be declared `unsafe`: `unsafe remote = ...`")
+                );
+            } else {
+                return Err(lh.error());
+            }
+            let lh = input.lookahead1();
+            if lh.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else if !lh.peek(End) {
+                return Err(lh.error());
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn set_once<T: Spanned>(arg: &mut Option<T>, value: T, name: &str) -> syn::Result<()> {
+    if arg.is_some() {
+        return Err(syn::Error::new(
+            value.span(),
+            format!("`{name}` specified more than once"),
+        ));
+    }
+    *arg = Some(value);
+    Ok(())
+}
+
+/// How the constructors generated by `#[pin_data(new)]` and `#[pin_data(builder)]` initialize a
+/// field.
+enum NewKind {
+    /// The field is initialized by an initializer parameter.
+    Init,
+    /// `#[new(value)]`: the field is initialized by a value parameter.
+    Value,
+    /// `#[new(default)]` or `#[new(default = expr)]`: the field is initialized without a
+    /// parameter.
+    Default(Option<Expr>),
+}
+
+impl Parse for NewKind {
     fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
         let lh = input.lookahead1();
-        if lh.peek(End) {
-            input.parse().map(Self::Nothing)
-        } else if lh.peek(kw::PinnedDrop) {
-            input.parse().map(Self::PinnedDrop)
+        if lh.peek(kw::value) {
+            input.parse::<kw::value>()?;
+            Ok(Self::Value)
+        } else if lh.peek(kw::default) {
+            input.parse::<kw::default>()?;
+            if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Ok(Self::Default(Some(input.parse()?)))
+            } else {
+                Ok(Self::Default(None))
+            }
         } else {
             Err(lh.error())
         }
@@ -39,6 +117,7 @@ struct FieldInfo<'a> {
     field: &'a Field,
     pinned: bool,
     cfg_attrs: Vec<&'a Attribute>,
+    new: NewKind,
 }
 
 pub(crate) fn pin_data(
@@ -68,6 +147,18 @@ pub(crate) fn pin_data(
         }
     };
 
+    if args.remote.is_some() {
+        // The remote struct has its own constructors and `Drop` implementation.
+        let unsupported = [
+            args.pinned_drop.map(|kw| (kw.span, "PinnedDrop")),
+            args.new.map(|kw| (kw.span, "new")),
+            args.builder.map(|kw| (kw.span, "builder")),
+        ];
+        if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+            return Err(dcx.error(span, format!("`{name}` cannot be combined with `remote`")));
+        }
+    }
+
     // The generics might contain the `Self` type. Since this macro will define a new type with the
     // same generics and bounds, this poses a problem: `Self` will refer to the new type as opposed
     // to this struct definition. Therefore we have to replace `Self` with the concrete name.
@@ -90,6 +181,29 @@ pub(crate) fn pin_data(
                 dcx.error(&field, "#[pin] attribute specified more than once");
             }
 
+            let mut new = None;
+            field.attrs.retain(|a| {
+                if !a.path().is_ident("new") {
+                    return true;
+                }
+                if args.new.is_none() && args.builder.is_none() {
+                    dcx.error(
+                        a,
+                        "`#[new]` requires `#[pin_data(new)]` or `#[pin_data(builder)]`",
+                    );
+                } else if new.is_some() {
+                    dcx.error(a, "#[new] attribute specified more than once");
+                } else {
+                    match a.parse_args() {
+                        Ok(kind) => new = Some(kind),
+                        Err(err) => {
+                            dcx.error(err.span(), err);
+                        }
+                    }
+                }
+                false
+            });
+
             let cfg_attrs = field
                 .attrs
                 .iter()
@@ -100,6 +214,7 @@ pub(crate) fn pin_data(
                 field: &*field,
                 pinned: pinned_count != 0,
                 cfg_attrs,
+                new: new.unwrap_or(NewKind::Init),
             }
         })
         .collect();
@@ -118,22 +233,68 @@ pub(crate) fn pin_data(
         }
     }
 
+    if let Some(remote) = &args.remote {
+        let the_pin_data = generate_the_pin_data(
+            &struct_.vis,
+            &struct_.ident,
+            &struct_.generics,
+            &fields,
+            Some(remote),
+        );
+        let remote_checks =
+            generate_remote_checks(&struct_.ident, &struct_.generics, &fields, remote);
+        return Ok(quote! {
+            #struct_
+            const _: () = {
+                #the_pin_data
+                #remote_checks
+            };
+        });
+    }
+
     let unpin_impl = generate_unpin_impl(&struct_.ident, &struct_.generics, &fields);
-    let drop_impl = generate_drop_impl(&struct_.ident, &struct_.generics, args);
+    let drop_impl = generate_drop_impl(&struct_.ident, &struct_.generics, &args);
+    let new = args
+        .new
+        .is_some()
+        .then(|| generate_new(&struct_.vis, &struct_.ident, &struct_.generics, &fields));
+    let builder = args.builder.is_some().then(|| {
+        generate_builder(
+            &struct_.vis,
+            &struct_.ident,
+            &struct_.generics,
+            &fields,
+            dcx,
+        )
+    });
     let projections =
         generate_projections(&struct_.vis, &struct_.ident, &struct_.generics, &fields);
-    let the_pin_data =
-        generate_the_pin_data(&struct_.vis, &struct_.ident, &struct_.generics, &fields);
+    let the_pin_data = generate_the_pin_data(
+        &struct_.vis,
+        &struct_.ident,
+        &struct_.generics,
+        &fields,
+        None,
+    );
+    let field_meta = field_meta::field_meta(
+        &struct_.ident,
+        &struct_.generics,
+        &struct_.fields,
+        quote!(PinDataSource),
+    );
 
     Ok(quote! {
         #struct_
         #projections
+        #new
+        #builder
         // We put the rest into this const item, because it then will not be accessible to anything
         // outside.
         const _: () = {
             #the_pin_data
             #unpin_impl
             #drop_impl
+            #field_meta
         };
     })
 }
@@ -216,9 +377,119 @@ fn generate_unpin_impl(
     }
 }
 
-fn generate_drop_impl(ident: &Ident, generics: &Generics, args: Args) -> TokenStream {
+/// Generates the checks that the struct annotated with `#[pin_data(remote = ...)]` mirrors the
+/// remote struct and that the remote struct upholds the guarantees of `#[pin_data]`.
+fn generate_remote_checks(
+    ident: &Ident,
+    generics: &Generics,
+    fields: &[FieldInfo<'_>],
+    remote: &Path,
+) -> TokenStream {
     let (impl_generics, ty_generics, whr) = generics.split_for_impl();
-    let has_pinned_drop = matches!(args, Args::PinnedDrop(_));
+    // The generic arguments of the patterns are inferred from the signatures of the checks.
+    let mut remote_pattern = remote.clone();
+    if let Some(last) = remote_pattern.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+    let (cfg_attrs, names): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .map(|f| (&f.cfg_attrs, f.field.ident.as_ref().unwrap()))
+        .unzip();
+    // Destructuring the remote struct without `..` ensures that all of its fields are mirrored and
+    // accessible, converting in both directions ensures that the fields have the same types.
+    let field_check = quote! {
+        #[allow(dead_code, non_snake_case)]
+        fn __check_remote_fields #impl_generics (remote: #remote) -> #ident #ty_generics
+            #whr
+        {
+            let #remote_pattern { #(#(#cfg_attrs)* #names,)* } = remote;
+            #ident { #(#(#cfg_attrs)* #names,)* }
+        }
+
+        #[allow(dead_code, non_snake_case)]
+        fn __check_mirror_fields #impl_generics (mirror: #ident #ty_generics) -> #remote
+            #whr
+        {
+            let #ident { #(#(#cfg_attrs)* #names,)* } = mirror;
+            #remote_pattern { #(#(#cfg_attrs)* #names,)* }
+        }
+    };
+    // Structurally pinned fields may only be initialized in place if the remote struct cannot be
+    // moved out of a `Pin`. Since it is not possible to add an `Unpin` implementation to a remote
+    // struct, it is required not to implement `Unpin`, which is checked by making the method
+    // resolution below ambiguous for `Unpin` types.
+    let unpin_check = fields.iter().any(|f| f.pinned).then(|| {
+        quote! {
+            trait RemoteWithPinnedFieldsMustNotBeUnpin<A> {
+                fn check() {}
+            }
+            impl<T: ?::core::marker::Sized> RemoteWithPinnedFieldsMustNotBeUnpin<()> for T {}
+            impl<T: ?::core::marker::Sized + ::core::marker::Unpin>
+                RemoteWithPinnedFieldsMustNotBeUnpin<u8> for T {}
+
+            #[allow(dead_code)]
+            fn __check_remote_unpin #impl_generics ()
+                #whr
+            {
+                let _ = <#remote as RemoteWithPinnedFieldsMustNotBeUnpin<_>>::check;
+            }
+        }
+    });
+    // A `Drop` implementation could move structurally pinned fields, in the same way as for local
+    // structs.
+    let drop_check = quote! {
+        trait MustNotImplDrop {}
+        impl<T: ::core::ops::Drop + ?::core::marker::Sized> MustNotImplDrop for T {}
+        impl #impl_generics MustNotImplDrop for #remote
+            #whr
+        {}
+    };
+    // The offsets are compared regardless of the `#[repr]` of the mirror, a mirror without a
+    // defined layout only compiles if the compiler happens to choose the same layout. The constant
+    // is also evaluated by `__pin_data`, since generic constants are only evaluated when they are
+    // used.
+    let msgs = names
+        .iter()
+        .map(|name| format!("the field `{name}` of the remote struct has a different offset"));
+    let layout_asserts = quote! {
+        #(
+            #(#cfg_attrs)*
+            ::core::assert!(
+                ::core::mem::offset_of!(#remote, #names) == ::core::mem::offset_of!(Self, #names),
+                #msgs,
+            );
+        )*
+        ::core::assert!(
+            ::core::mem::size_of::<#remote>() == ::core::mem::size_of::<Self>(),
+            "the remote struct has a different size",
+        );
+    };
+    let layout_check = quote! {
+        impl #impl_generics #ident #ty_generics
+            #whr
+        {
+            const __CHECK_REMOTE_LAYOUT: () = {
+                #layout_asserts
+            };
+        }
+    };
+    // Without generics, the constant can be evaluated right away.
+    let layout_eval = generics
+        .params
+        .is_empty()
+        .then(|| quote!(const _: () = #ident::__CHECK_REMOTE_LAYOUT;));
+    quote! {
+        #field_check
+        #unpin_check
+        #drop_check
+        #layout_check
+        #layout_eval
+    }
+}
+
+fn generate_drop_impl(ident: &Ident, generics: &Generics, args: &Args) -> TokenStream {
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+    let has_pinned_drop = args.pinned_drop.is_some();
     // We need to disallow normal `Drop` implementation, the exact behavior depends on whether
     // `PinnedDrop` was specified in `args`.
     if has_pinned_drop {
@@ -358,13 +629,325 @@ fn generate_projections(
     }
 }
 
+/// Generates the `new` function of `#[pin_data(new)]`, which initializes every field with a
+/// parameter of the same name.
+fn generate_new(
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    fields: &[FieldInfo<'_>],
+) -> TokenStream {
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+    let mut params = vec![];
+    let mut inits = vec![];
+    for field in fields {
+        let Field {
+            ident: field_ident,
+            ty,
+            ..
+        } = field.field;
+        let cfg_attrs = &field.cfg_attrs;
+        let field_ident = field_ident
+            .as_ref()
+            .expect("only structs with named fields are supported");
+        let init = match &field.new {
+            NewKind::Init if is_phantom_pinned(ty) => {
+                quote!(#field_ident: ::core::marker::PhantomPinned)
+            }
+            NewKind::Init => {
+                let init_trait = if field.pinned {
+                    quote!(PinInit)
+                } else {
+                    quote!(Init)
+                };
+                params.push(quote! {
+                    #(#cfg_attrs)*
+                    #field_ident: impl ::pin_init::#init_trait<#ty, __E>
+                });
+                quote!(#field_ident <- #field_ident)
+            }
+            NewKind::Value => {
+                params.push(quote! {
+                    #(#cfg_attrs)*
+                    #field_ident: #ty
+                });
+                quote!(#field_ident)
+            }
+            NewKind::Default(None) => {
+                quote!(#field_ident: ::core::default::Default::default())
+            }
+            NewKind::Default(Some(expr)) => quote!(#field_ident: #expr),
+        };
+        inits.push(quote! {
+            #(#cfg_attrs)*
+            #init
+        });
+    }
+    let docs = format!(" Creates a pin-initializer for [`{ident}`] from its fields.");
+    quote! {
+        impl #impl_generics #ident #ty_generics
+            #whr
+        {
+            #[doc = #docs]
+            #[inline]
+            #vis fn new<__E>(#(#params),*) -> impl ::pin_init::PinInit<Self, __E> {
+                ::pin_init::pin_init!(Self {
+                    #(#inits,)*
+                }? __E)
+            }
+        }
+    }
+}
+
+/// Generates the typestate builder of `#[pin_data(builder)]`.
+///
+/// The builder has a type parameter for every field that does not contain `PhantomPinned`. It is
+/// `Unset` until the field has been given to its setter and then the type of the given
+/// initializer (or `Set<I>` for fields with a default). `build` is only implemented once all
+/// fields without a default have been set.
+fn generate_builder(
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    fields: &[FieldInfo<'_>],
+    dcx: &mut DiagCtxt,
+) -> TokenStream {
+    if let Some(field) = fields.iter().find(|f| !f.cfg_attrs.is_empty()) {
+        dcx.error(
+            field.cfg_attrs[0],
+            "`#[pin_data(builder)]` does not support fields with `#[cfg]`",
+        );
+        return quote!();
+    }
+    let builder = format_ident!("{ident}Builder");
+    let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+    let turbofish = ty_generics.as_turbofish();
+    let struct_params: Vec<_> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect();
+    // Defaults of generic parameters are not allowed in impls and they would precede `__E` in the
+    // builder struct, so they are removed.
+    let mut decl_generics = generics.clone();
+    for param in &mut decl_generics.params {
+        match param {
+            GenericParam::Lifetime(_) => {}
+            GenericParam::Type(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+        }
+    }
+    let struct_decl_params: Vec<_> = decl_generics.params.iter().collect();
+    let unset = quote!(::pin_init::__internal::Unset);
+
+    // The fields stored in the builder together with their type parameter.
+    let stored: Vec<_> = fields
+        .iter()
+        .filter(|f| !(matches!(f.new, NewKind::Init) && is_phantom_pinned(&f.field.ty)))
+        .enumerate()
+        .map(|(i, f)| (f, format_ident!("__F{i}")))
+        .collect();
+    let field_names: Vec<_> = stored
+        .iter()
+        .map(|(f, _)| f.field.ident.as_ref().unwrap())
+        .collect();
+    let params: Vec<_> = stored.iter().map(|(_, param)| param).collect();
+    let unsets = stored.iter().map(|_| &unset);
+
+    let setters = stored.iter().enumerate().map(|(i, (f, _))| {
+        let Field {
+            ident: field_ident,
+            ty,
+            ..
+        } = f.field;
+        let field_ident = field_ident.as_ref().unwrap();
+        let other_params = params
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, param)| param);
+        let with = |replacement: TokenStream| {
+            params.iter().enumerate().map(move |(j, param)| {
+                if j == i {
+                    replacement.clone()
+                } else {
+                    quote!(#param)
+                }
+            })
+        };
+        let init_trait = if f.pinned {
+            quote!(PinInit)
+        } else {
+            quote!(Init)
+        };
+        let (generic, arg, stored_ty, value) = match &f.new {
+            NewKind::Init => (
+                quote!(<__I: ::pin_init::#init_trait<#ty, __E>>),
+                quote!(__I),
+                quote!(__I),
+                quote!(#field_ident),
+            ),
+            NewKind::Value => (quote!(), quote!(#ty), quote!(#ty), quote!(#field_ident)),
+            NewKind::Default(_) => (
+                quote!(<__I: ::pin_init::#init_trait<#ty, __E>>),
+                quote!(__I),
+                quote!(::pin_init::__internal::Set<__I>),
+                quote!(::pin_init::__internal::Set(#field_ident)),
+            ),
+        };
+        let unset_params = with(unset.clone());
+        let set_params = with(stored_ty);
+        let others = field_names.iter().filter(|name| **name != field_ident);
+        let docs = format!(" Sets the initializer of the `{field_ident}` field.");
+        quote! {
+            impl<#(#struct_decl_params,)* __E, #(#other_params),*>
+                #builder<#(#struct_params,)* __E, #(#unset_params),*>
+                #whr
+            {
+                #[doc = #docs]
+                #[inline]
+                #vis fn #field_ident #generic(
+                    self,
+                    #field_ident: #arg,
+                ) -> #builder<#(#struct_params,)* __E, #(#set_params),*> {
+                    #builder {
+                        #field_ident: #value,
+                        #(#others: self.#others,)*
+                        ___pin_phantom_data: self.___pin_phantom_data,
+                    }
+                }
+            }
+        }
+    });
+    let setters: Vec<_> = setters.collect();
+
+    let mut build_bounds = vec![];
+    let mut build_generics = vec![];
+    let mut build_params = vec![];
+    for (f, param) in &stored {
+        let ty = &f.field.ty;
+        let init_trait = if f.pinned {
+            quote!(PinInit)
+        } else {
+            quote!(Init)
+        };
+        match &f.new {
+            NewKind::Init => {
+                build_bounds.push(quote!(#param: ::pin_init::#init_trait<#ty, __E>));
+                build_generics.push(param);
+                build_params.push(quote!(#param));
+            }
+            NewKind::Value => build_params.push(quote!(#ty)),
+            NewKind::Default(_) => {
+                build_bounds.push(quote!(#param: ::pin_init::__internal::OptionalInit<#ty, __E>));
+                build_bounds.push(quote! {
+                    <#param as ::pin_init::__internal::OptionalInit<#ty, __E>>::Init:
+                        ::pin_init::#init_trait<#ty, __E>
+                });
+                build_generics.push(param);
+                build_params.push(quote!(#param));
+            }
+        }
+    }
+    let predicates = whr.iter().flat_map(|whr| &whr.predicates);
+    let inits = fields.iter().map(|f| {
+        let field_ident = f.field.ident.as_ref().unwrap();
+        match &f.new {
+            NewKind::Init if is_phantom_pinned(&f.field.ty) => {
+                quote!(#field_ident: ::core::marker::PhantomPinned)
+            }
+            NewKind::Init => quote!(#field_ident <- #field_ident),
+            NewKind::Value => quote!(#field_ident),
+            NewKind::Default(default) => {
+                let default = match default {
+                    Some(expr) => quote!(#expr),
+                    None => quote!(::core::default::Default::default()),
+                };
+                quote!(#field_ident <- #field_ident.or_else(|| #default))
+            }
+        }
+    });
+
+    let builder_docs = format!(
+        " Builder of a pin-initializer for [`{ident}`], created by \
+         [`{ident}::pin_init_builder`]."
+    );
+    let ctor_docs = format!(
+        " Creates a builder of a pin-initializer for [`{ident}`].\n\n \
+         The initializers of the fields are given to the setters of the same name, \
+         [`build`]({builder}::build) then returns the pin-initializer."
+    );
+    quote! {
+        #[doc = #builder_docs]
+        #[must_use]
+        #vis struct #builder<#(#struct_decl_params,)* __E, #(#params),*>
+            #whr
+        {
+            #(#field_names: #params,)*
+            ___pin_phantom_data: ::pin_init::__internal::PhantomInvariant<(__E, #ident #ty_generics)>,
+        }
+
+        impl #impl_generics #ident #ty_generics
+            #whr
+        {
+            #[doc = #ctor_docs]
+            #[inline]
+            #vis fn pin_init_builder<__E>() -> #builder<#(#struct_params,)* __E, #(#unsets),*> {
+                #builder {
+                    #(#field_names: ::pin_init::__internal::Unset,)*
+                    ___pin_phantom_data: ::pin_init::__internal::PhantomInvariant::new(),
+                }
+            }
+        }
+
+        #(#setters)*
+
+        impl<#(#struct_decl_params,)* __E, #(#build_generics),*>
+            #builder<#(#struct_params,)* __E, #(#build_params),*>
+        where
+            #(#predicates,)*
+            #(#build_bounds,)*
+        {
+            /// Returns the pin-initializer, which initializes the fields in declaration order.
+            #[inline]
+            #vis fn build(self) -> impl ::pin_init::PinInit<#ident #ty_generics, __E> {
+                let #builder { #(#field_names,)* .. } = self;
+                ::pin_init::pin_init!(#ident #turbofish {
+                    #(#inits,)*
+                }? __E)
+            }
+        }
+    }
+}
+
 fn generate_the_pin_data(
     vis: &Visibility,
     struct_name: &Ident,
     generics: &Generics,
     fields: &[FieldInfo<'_>],
+    remote: Option<&Path>,
 ) -> TokenStream {
     let (impl_generics, ty_generics, whr) = generics.split_for_impl();
+    // The type that is initialized, which differs from the annotated struct for remote structs.
+    let target = match remote {
+        Some(remote) => quote!(#remote),
+        None => quote!(#struct_name #ty_generics),
+    };
+    let layout_check = remote.map(|_| quote!(let () = Self::__CHECK_REMOTE_LAYOUT;));
+    let field_check_slot = match remote {
+        Some(_) => quote!(slot as *mut #struct_name #ty_generics),
+        None => quote!(slot),
+    };
 
     // For every field, we create an initializing projection function according to its projection
     // type. If a field is structurally pinned, we create a `Slot` with `Pinned` which must be
@@ -398,7 +981,7 @@ fn generate_the_pin_data(
                 #[inline(always)]
                 #vis unsafe fn #field_name(
                     self,
-                    slot: *mut #struct_name #ty_generics,
+                    slot: *mut #target,
                 ) -> ::pin_init::__internal::Slot<::pin_init::__internal::#pin_marker, #ty> {
                     // SAFETY:
                     // - If `#pin_marker` is `Pinned`, the corresponding field is structurally
@@ -437,12 +1020,19 @@ fn generate_the_pin_data(
             #[inline(always)]
             #vis fn __make_closure<__F, __E>(self, f: __F) -> __F
             where
-                __F: FnOnce(*mut #struct_name #ty_generics) ->
+                __F: FnOnce(*mut #target) ->
                     ::core::result::Result<::pin_init::__internal::InitOk, __E>,
             {
                 f
             }
 
+            /// Returns the pointer that the field check of the initializer macros uses to check that
+            /// all fields of the annotated struct have been initialized.
+            #[inline(always)]
+            #vis fn __field_check_slot(self, slot: *mut #target) -> *mut #struct_name #ty_generics {
+                #field_check_slot
+            }
+
             #field_accessors
         }
 
@@ -454,6 +1044,7 @@ fn generate_the_pin_data(
             type PinData = __ThePinData #ty_generics;
 
             unsafe fn __pin_data() -> Self::PinData {
+                #layout_check
                 __ThePinData { __phantom: ::pin_init::__internal::PhantomInvariant::new() }
             }
         }
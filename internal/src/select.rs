@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Block, Expr, ExprIf, Stmt};
+
+use crate::diagnostics::{DiagCtxt, ErrorGuaranteed};
+
+pub(crate) fn expand(mut input: Expr, dcx: &mut DiagCtxt) -> Result<TokenStream, ErrorGuaranteed> {
+    let mut branches = vec![];
+    match &mut input {
+        Expr::Match(expr) => {
+            for arm in &mut expr.arms {
+                // Block bodies do not need a comma, but they will be wrapped in a call.
+                arm.comma.get_or_insert_with(Default::default);
+                branches.push(&mut *arm.body);
+            }
+        }
+        Expr::If(expr) => if_branches(expr, &mut branches, dcx)?,
+        _ => return Err(dcx.error(&input, "expected `match` or `if`")),
+    }
+    // The `n`th branch is wrapped in `n` times `Right` and a `Left`, except for the last one, which
+    // only needs the `Right`s.
+    let count = branches.len();
+    for (i, branch) in branches.into_iter().enumerate() {
+        let mut wrapped = if i + 1 == count {
+            quote!(#branch)
+        } else {
+            quote!(::pin_init::EitherInit::Left(#branch))
+        };
+        for _ in 0..i {
+            wrapped = quote!(::pin_init::EitherInit::Right(#wrapped));
+        }
+        *branch = parse_quote!(#wrapped);
+    }
+    Ok(quote!(#input))
+}
+
+/// Collects the branches of an `if`/`else if`/`else` chain.
+fn if_branches<'a>(
+    expr: &'a mut ExprIf,
+    branches: &mut Vec<&'a mut Expr>,
+    dcx: &mut DiagCtxt,
+) -> Result<(), ErrorGuaranteed> {
+    let Some((_, else_branch)) = &mut expr.else_branch else {
+        return Err(dcx.error(
+            expr.if_token,
+            "expected an `else` branch, every branch has to return an initializer",
+        ));
+    };
+    branches.push(nest_block(&mut expr.then_branch));
+    match &mut **else_branch {
+        Expr::If(expr) => if_branches(expr, branches, dcx),
+        Expr::Block(expr) => {
+            branches.push(nest_block(&mut expr.block));
+            Ok(())
+        }
+        _ => unreachable!("`else` is followed by `if` or a block"),
+    }
+}
+
+/// Moves the contents of `block` into an inner block expression and returns it, so the branch can
+/// be wrapped while the outer block remains.
+fn nest_block(block: &mut Block) -> &mut Expr {
+    *block = parse_quote!({ #block });
+    match block.stmts.last_mut() {
+        Some(Stmt::Expr(expr, None)) => expr,
+        _ => unreachable!("the block consists of a single expression"),
+    }
+}
@@ -4,15 +4,23 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{parse_quote, Data, DeriveInput, Field, Fields};
 
-use crate::{diagnostics::ErrorGuaranteed, DiagCtxt};
+use crate::{diagnostics::ErrorGuaranteed, field_meta, DiagCtxt};
 
 pub(crate) fn derive(
     input: DeriveInput,
     dcx: &mut DiagCtxt,
 ) -> Result<TokenStream, ErrorGuaranteed> {
-    let fields = match input.data {
-        Data::Struct(data_struct) => data_struct.fields,
-        Data::Union(data_union) => Fields::Named(data_union.fields),
+    let (fields, field_meta) = match input.data {
+        Data::Struct(data_struct) => {
+            let field_meta = field_meta::field_meta(
+                &input.ident,
+                &input.generics,
+                &data_struct.fields,
+                quote!(ZeroableSource),
+            );
+            (data_struct.fields, field_meta)
+        }
+        Data::Union(data_union) => (Fields::Named(data_union.fields), quote!()),
         Data::Enum(data_enum) => {
             return Err(dcx.error(data_enum.enum_token, "cannot derive `Zeroable` for an enum"));
         }
@@ -30,6 +38,7 @@ pub(crate) fn derive(
         unsafe impl #impl_gen ::pin_init::Zeroable for #name #ty_gen
             #whr
         {}
+        #field_meta
         const _: () = {
             fn assert_zeroable<T: ?::core::marker::Sized + ::pin_init::Zeroable>() {}
             fn ensure_zeroable #impl_gen ()
@@ -47,9 +56,17 @@ pub(crate) fn maybe_derive(
     input: DeriveInput,
     dcx: &mut DiagCtxt,
 ) -> Result<TokenStream, ErrorGuaranteed> {
-    let fields = match input.data {
-        Data::Struct(data_struct) => data_struct.fields,
-        Data::Union(data_union) => Fields::Named(data_union.fields),
+    let (fields, field_meta) = match input.data {
+        Data::Struct(data_struct) => {
+            let field_meta = field_meta::field_meta(
+                &input.ident,
+                &input.generics,
+                &data_struct.fields,
+                quote!(ZeroableSource),
+            );
+            (data_struct.fields, field_meta)
+        }
+        Data::Union(data_union) => (Fields::Named(data_union.fields), quote!()),
         Data::Enum(data_enum) => {
             return Err(dcx.error(data_enum.enum_token, "cannot derive `Zeroable` for an enum"));
         }
@@ -74,5 +91,6 @@ pub(crate) fn maybe_derive(
         unsafe impl #impl_gen ::pin_init::Zeroable for #name #ty_gen
             #whr
         {}
+        #field_meta
     })
 }
@@ -7,6 +7,12 @@
 
 use super::*;
 
+#[cfg(feature = "debug-poison")]
+pub use crate::poison::{
+    check_field, poison_field, FieldMeta, FieldProbe, NoUninit, NoUninitProbe, PinDataSource,
+    UncheckedProbe, ZeroableSource,
+};
+
 /// Zero-sized type used to mark a type as invariant.
 ///
 /// This is a polyfill for the [unstable type] in the standard library of the same name.
@@ -121,6 +127,13 @@ impl<T: ?Sized> AllData<T> {
     {
         f
     }
+
+    /// Returns the pointer that the field check of the initializer macros uses to check that all
+    /// fields have been initialized.
+    #[inline(always)]
+    pub fn __field_check_slot(self, slot: *mut T) -> *mut T {
+        slot
+    }
 }
 
 // SAFETY: TODO.
@@ -134,26 +147,10 @@ unsafe impl<T: ?Sized> HasInitData for T {
 
 /// Stack initializer helper type. Use [`stack_pin_init`] instead of this primitive.
 ///
-/// # Invariants
-///
-/// If `self.is_init` is true, then `self.value` is initialized.
+/// This is a [`PinSlot`] that the macros initialize right after creating it.
 ///
 /// [`stack_pin_init`]: crate::stack_pin_init
-pub struct StackInit<T> {
-    value: MaybeUninit<T>,
-    is_init: bool,
-}
-
-impl<T> Drop for StackInit<T> {
-    #[inline]
-    fn drop(&mut self) {
-        if self.is_init {
-            // SAFETY: As we are being dropped, we only call this once. And since `self.is_init` is
-            // true, `self.value` is initialized.
-            unsafe { self.value.assume_init_drop() };
-        }
-    }
-}
+pub struct StackInit<T>(PinSlot<T>);
 
 impl<T> StackInit<T> {
     /// Creates a new [`StackInit<T>`] that is uninitialized. Use [`stack_pin_init`] instead of this
@@ -162,30 +159,14 @@ impl<T> StackInit<T> {
     /// [`stack_pin_init`]: crate::stack_pin_init
     #[inline]
     pub fn uninit() -> Self {
-        Self {
-            value: MaybeUninit::uninit(),
-            is_init: false,
-        }
+        Self(PinSlot::new())
     }
 
     /// Initializes the contents and returns the result.
     #[inline]
     pub fn init<E>(self: Pin<&mut Self>, init: impl PinInit<T, E>) -> Result<Pin<&mut T>, E> {
-        // SAFETY: We never move out of `this`.
-        let this = unsafe { Pin::into_inner_unchecked(self) };
-        // The value is currently initialized, so it needs to be dropped before we can reuse
-        // the memory (this is a safety guarantee of `Pin`).
-        if this.is_init {
-            this.is_init = false;
-            // SAFETY: `this.is_init` was true and therefore `this.value` is initialized.
-            unsafe { this.value.assume_init_drop() };
-        }
-        // SAFETY: The memory slot is valid and this type ensures that it will stay pinned.
-        unsafe { init.__pinned_init(this.value.as_mut_ptr())? };
-        // INVARIANT: `this.value` is initialized above.
-        this.is_init = true;
-        // SAFETY: The slot is now pinned, since we will never give access to `&mut T`.
-        Ok(unsafe { Pin::new_unchecked(this.value.assume_init_mut()) })
+        // SAFETY: The slot is structurally pinned, it is never moved out of `self`.
+        unsafe { self.map_unchecked_mut(|this| &mut this.0) }.try_pin_init(init)
     }
 }
 
@@ -261,6 +242,60 @@ impl<P, T: ?Sized> Slot<P, T> {
         // - If `P` is `Pinned`, `self.ptr` is pinned.
         unsafe { DropGuard::new(self.ptr) }
     }
+
+    /// Returns the pointer to the uninitialized field.
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr
+    }
+
+    /// Projects the slot of a field of `T`, used when initializing the fields of `T` one by one.
+    ///
+    /// The field is only pinned if both `T` and the field are structurally pinned.
+    ///
+    /// # Safety
+    ///
+    /// `field` must point to a field of `*self.as_ptr()`.
+    #[inline(always)]
+    pub unsafe fn project<Q, U: ?Sized>(&self, field: Slot<Q, U>) -> Slot<P::Output, U>
+    where
+        P: Project<Q>,
+    {
+        // SAFETY:
+        // - `field.ptr` is valid, properly aligned and points to uninitialized and exclusively
+        //   accessed memory per type invariant.
+        // - `P::Output` is only `Pinned` if `P` and `Q` are `Pinned`, so `self.ptr` is
+        //   structurally pinned and `field.ptr` is a structurally pinned field of it.
+        unsafe { Slot::new(field.ptr) }
+    }
+
+    /// Transfers the ownership of the initialized field to a guard.
+    ///
+    /// # Safety
+    ///
+    /// `*self.as_ptr()` must be fully initialized.
+    #[inline(always)]
+    pub unsafe fn assume_init(self) -> DropGuard<P, T> {
+        // SAFETY:
+        // - `self.ptr` is valid and properly aligned per type invariant.
+        // - `*self.ptr` is initialized by the safety requirement and the ownership is transferred
+        //   to the guard.
+        // - If `P` is `Pinned`, `self.ptr` is pinned.
+        unsafe { DropGuard::new(self.ptr) }
+    }
+}
+
+/// Combines the pin marker of a struct with the one of its field.
+pub trait Project<Q> {
+    type Output;
+}
+
+impl<Q> Project<Q> for Pinned {
+    type Output = Q;
+}
+
+impl<Q> Project<Q> for Unpinned {
+    type Output = Unpinned;
 }
 
 impl<T: ?Sized> Slot<Unpinned, T> {
@@ -310,6 +345,8 @@ impl<T: ?Sized> Slot<Pinned, T> {
 pub struct DropGuard<P, T: ?Sized> {
     ptr: *mut T,
     phantom: PhantomData<P>,
+    #[cfg(feature = "trace")]
+    field: Option<crate::trace::Field>,
 }
 
 impl<P, T: ?Sized> DropGuard<P, T> {
@@ -328,6 +365,8 @@ impl<P, T: ?Sized> DropGuard<P, T> {
         Self {
             ptr,
             phantom: PhantomData,
+            #[cfg(feature = "trace")]
+            field: None,
         }
     }
 }
@@ -354,11 +393,114 @@ impl<T: ?Sized> DropGuard<Pinned, T> {
 impl<P, T: ?Sized> Drop for DropGuard<P, T> {
     #[inline]
     fn drop(&mut self) {
+        #[cfg(feature = "trace")]
+        if let Some(field) = self.field {
+            field.emit(crate::trace::Phase::Rollback);
+        }
         // SAFETY: `self.ptr` is valid, properly aligned and `*self.ptr` is owned by this guard.
         unsafe { ptr::drop_in_place(self.ptr) }
     }
 }
 
+/// The state of a field of a `#[pin_data(builder)]` builder that has not been set.
+pub struct Unset;
+
+/// The state of a field with a default of a `#[pin_data(builder)]` builder that has been set.
+pub struct Set<I>(pub I);
+
+/// The initializer of a field with a default of a `#[pin_data(builder)]` builder.
+pub trait OptionalInit<T, E> {
+    /// The initializer given to the setter.
+    type Init;
+
+    /// Returns the initializer given to the setter or one writing the value returned by `default`.
+    fn or_else(self, default: impl FnOnce() -> T) -> EitherInit<Self::Init, Result<T, E>, T, E>;
+}
+
+impl<T, E> OptionalInit<T, E> for Unset {
+    type Init = Result<T, E>;
+
+    #[inline]
+    fn or_else(self, default: impl FnOnce() -> T) -> EitherInit<Self::Init, Result<T, E>, T, E> {
+        EitherInit::Right(Ok(default()))
+    }
+}
+
+impl<T, E, I> OptionalInit<T, E> for Set<I> {
+    type Init = I;
+
+    #[inline]
+    fn or_else(self, _: impl FnOnce() -> T) -> EitherInit<Self::Init, Result<T, E>, T, E> {
+        EitherInit::Left(self.0)
+    }
+}
+
+/// Runs the `else` block of a code item in an initializer when dropped, which happens when a
+/// later field fails to initialize.
+pub struct UndoGuard<F: FnOnce()>(Option<F>);
+
+impl<F: FnOnce()> UndoGuard<F> {
+    #[inline(always)]
+    pub fn new(undo: F) -> Self {
+        Self(Some(undo))
+    }
+
+    /// Drops the `else` block without running it.
+    #[inline(always)]
+    pub fn dismiss(mut self) {
+        self.0 = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for UndoGuard<F> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(undo) = self.0.take() {
+            undo();
+        }
+    }
+}
+
+/// Traces the initialization of a field when the `trace` feature is enabled, does nothing
+/// otherwise.
+///
+/// Dropping the span reports a failure, [`FieldSpan::success`] reports a success.
+pub struct FieldSpan {
+    #[cfg(feature = "trace")]
+    field: crate::trace::Field,
+}
+
+impl FieldSpan {
+    /// Starts the initialization of `field` of `*slot`.
+    #[inline(always)]
+    pub fn start<T: ?Sized>(_slot: *mut T, _field: &'static str) -> Self {
+        Self {
+            #[cfg(feature = "trace")]
+            field: crate::trace::Field::start::<T>(_field),
+        }
+    }
+
+    /// Finishes the initialization of the field owned by `_guard`, which reports a rollback when
+    /// it is dropped.
+    #[inline(always)]
+    pub fn success<P, T: ?Sized>(self, _guard: &mut DropGuard<P, T>) {
+        #[cfg(feature = "trace")]
+        {
+            let field = self.field;
+            core::mem::forget(self);
+            field.finish(crate::trace::Phase::Success);
+            _guard.field = Some(field);
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Drop for FieldSpan {
+    fn drop(&mut self) {
+        self.field.finish(crate::trace::Phase::Failure);
+    }
+}
+
 /// Token used by `PinnedDrop` to prevent calling the function without creating this unsafely
 /// created struct. This is needed, because the `drop` function is safe, but should not be called
 /// manually.
@@ -402,3 +544,128 @@ unsafe impl<T: ?Sized> PinInit<T, ()> for AlwaysFail<T> {
         Err(())
     }
 }
+
+/// Initializer for array fields created by `for` loops in the `init!` macro.
+///
+/// It only initializes arrays of length `N`, which is either inferred for `for i in ..` or given by
+/// the range `0..N`.
+pub struct ArrayFor<T: ?Sized, F, const N: usize>(ArrayInit<T, F>);
+
+/// Creates the initializer for `for i in 0..N { field[i] <- make_init(i) }`.
+#[inline]
+pub fn array_for<T: ?Sized, F, const N: usize>(make_init: F) -> ArrayFor<T, F, N> {
+    ArrayFor(ArrayInit(make_init, PhantomInvariant::new()))
+}
+
+// SAFETY: Delegates to `ArrayInit`.
+unsafe impl<T, F, I, E, const N: usize> PinInit<[T; N], E> for ArrayFor<T, F, N>
+where
+    F: FnMut(usize) -> I,
+    I: PinInit<T, E>,
+{
+    #[inline(always)]
+    unsafe fn __pinned_init(self, slot: *mut [T; N]) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.0.__pinned_init(slot) }
+    }
+}
+
+// SAFETY: Delegates to `ArrayInit`.
+unsafe impl<T, F, I, E, const N: usize> Init<[T; N], E> for ArrayFor<T, F, N>
+where
+    F: FnMut(usize) -> I,
+    I: Init<T, E>,
+{
+    #[inline(always)]
+    unsafe fn __init(self, slot: *mut [T; N]) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.0.__init(slot) }
+    }
+}
+
+/// Records `field` of the type pointed to by `slot` in the path of `err`, together with `index` if
+/// the field is an array initialized by a `for` item.
+///
+/// Used by the `init!` macro when the `#[field_errors]` attribute is given.
+#[cold]
+pub fn field_error<T: ?Sized, E>(
+    _slot: *mut T,
+    field: &'static str,
+    index: Option<usize>,
+    err: InitError<E>,
+) -> InitError<E> {
+    let name = core::any::type_name::<T>();
+    // Strip the module path, but keep the generics of the type.
+    let generics = name.find('<').unwrap_or(name.len());
+    let start = name[..generics].rfind("::").map_or(0, |i| i + 2);
+    err.in_field(&name[start..], field, index)
+}
+
+// With `#[field_errors]`, the error of a field initializer is converted into an `InitError` at the
+// call site by method resolution: `(&err).__field_error_kind()` picks `NestedFieldError` if `err`
+// already is an `InitError`, and `SourceFieldError` (which needs another auto-ref) otherwise. The
+// two cases cannot be expressed as non-overlapping impls of a single trait.
+
+/// Selects [`NestedKind`] for errors that already record a path.
+pub trait NestedFieldError {
+    #[inline(always)]
+    fn __field_error_kind(&self) -> NestedKind {
+        NestedKind
+    }
+}
+
+impl<E> NestedFieldError for InitError<E> {}
+
+/// Selects [`SourceKind`] for all other errors.
+pub trait SourceFieldError {
+    #[inline(always)]
+    fn __field_error_kind(&self) -> SourceKind {
+        SourceKind
+    }
+}
+
+impl<E1> SourceFieldError for &E1 {}
+
+/// Conversion of an [`InitError`] returned by a field initializer.
+pub struct NestedKind;
+
+impl NestedKind {
+    /// Converts the source of `err`, keeping its path.
+    #[inline(always)]
+    pub fn convert<E: From<E1>, E1>(self, err: InitError<E1>) -> InitError<E> {
+        err.map_source(E::from)
+    }
+}
+
+/// Conversion of any other error returned by a field initializer.
+pub struct SourceKind;
+
+impl SourceKind {
+    /// Converts `err` into `E` and starts an empty path.
+    #[inline(always)]
+    pub fn convert<E: From<E1>, E1>(self, err: E1) -> InitError<E> {
+        InitError::from(E::from(err))
+    }
+}
+
+/// Initializer of an element of an array initialized by a `for` item with `#[field_errors]`, which
+/// returns the index of the element together with the error.
+pub struct Indexed<I>(pub usize, pub I);
+
+// SAFETY: Delegates to `I`.
+unsafe impl<T: ?Sized, E, I: PinInit<T, E>> PinInit<T, (usize, E)> for Indexed<I> {
+    #[inline(always)]
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), (usize, E)> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.1.__pinned_init(slot) }.map_err(|err| (self.0, err))
+    }
+}
+
+// SAFETY: Delegates to `I`.
+unsafe impl<T: ?Sized, E, I: Init<T, E>> Init<T, (usize, E)> for Indexed<I> {
+    #[inline(always)]
+    unsafe fn __init(self, slot: *mut T) -> Result<(), (usize, E)> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.1.__init(slot) }.map_err(|err| (self.0, err))
+    }
+}
@@ -1,18 +1,29 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+#[cfg(feature = "dst")]
+use alloc::alloc::{Allocator, Global};
 #[cfg(all(feature = "alloc", not(feature = "std")))]
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, rc::Rc, sync::Arc, vec::Vec};
 #[cfg(feature = "alloc")]
 use core::alloc::AllocError;
-use core::{mem::MaybeUninit, pin::Pin};
+#[cfg(feature = "dst")]
+use core::{alloc::Layout, marker::Unsize, ptr::Pointee};
+use core::{convert::Infallible, mem::MaybeUninit, pin::Pin, ptr};
 #[cfg(feature = "std")]
-use std::sync::Arc;
+use std::{rc::Rc, sync::Arc};
 
 #[cfg(not(feature = "alloc"))]
 type AllocError = core::convert::Infallible;
 
+/// The error of a failed reservation in [`InPlaceExtend`].
+#[cfg(feature = "alloc")]
+type ReserveError = AllocError;
+#[cfg(not(feature = "alloc"))]
+type ReserveError = std::collections::TryReserveError;
+
 use crate::{
-    init_from_closure, pin_init_from_closure, InPlaceWrite, Init, PinInit, ZeroableOption,
+    init_from_closure, init_slice_from_fn, pin_init_from_closure, pin_init_slice_from_fn, poison,
+    InPlaceWrite, Init, PinInit, SliceBuilder, ZeroableOption,
 };
 
 pub extern crate alloc;
@@ -64,6 +75,258 @@ pub trait InPlaceInit<T>: Sized {
     }
 }
 
+/// Smart pointer to an unsized type that can initialize memory in-place.
+///
+/// This allocates a smart pointer for a sized type `T`, initializes it in-place and then converts
+/// it to a smart pointer to `U`, which is usually a trait object that `T` implements. Since the
+/// value is pinned before the conversion, this works for `!Unpin` types.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::pin::Pin;
+/// use pin_init::*;
+///
+/// trait Driver {
+///     fn probe(self: Pin<&Self>) -> usize;
+/// }
+///
+/// #[pin_data]
+/// struct Counter {
+///     #[pin]
+///     count: CMutex<usize>,
+/// }
+///
+/// impl Driver for Counter {
+///     fn probe(self: Pin<&Self>) -> usize {
+///         *self.count.lock()
+///     }
+/// }
+///
+/// fn load<D: Driver + 'static>(init: impl PinInit<D>) -> Pin<Box<dyn Driver>> {
+///     Box::<dyn Driver>::pin_init_unsized(init).unwrap()
+/// }
+///
+/// let driver = load(pin_init!(Counter { count <- CMutex::new(7) }));
+/// assert_eq!(driver.as_ref().probe(), 7);
+/// ```
+#[cfg(feature = "dst")]
+pub trait InPlaceInitUnsized<U: ?Sized>: Sized {
+    /// Use the given pin-initializer to pin-initialize a `T` inside of a new smart pointer and
+    /// convert it to a smart pointer to `U`.
+    fn try_pin_init_unsized<T, E>(init: impl PinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>;
+
+    /// Use the given pin-initializer to pin-initialize a `T` inside of a new smart pointer and
+    /// convert it to a smart pointer to `U`.
+    fn pin_init_unsized<T>(init: impl PinInit<T>) -> Result<Pin<Self>, AllocError>
+    where
+        T: Unsize<U>,
+    {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            pin_init_from_closure(|slot| match init.__pinned_init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        Self::try_pin_init_unsized::<T, _>(init)
+    }
+
+    /// Use the given initializer to in-place initialize a `T` inside of a new smart pointer and
+    /// convert it to a smart pointer to `U`.
+    fn try_init_unsized<T, E>(init: impl Init<T, E>) -> Result<Self, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>;
+
+    /// Use the given initializer to in-place initialize a `T` inside of a new smart pointer and
+    /// convert it to a smart pointer to `U`.
+    fn init_unsized<T>(init: impl Init<T>) -> Result<Self, AllocError>
+    where
+        T: Unsize<U>,
+    {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            init_from_closure(|slot| match init.__init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        Self::try_init_unsized::<T, _>(init)
+    }
+}
+
+/// Smart pointer to a dynamically sized type that can initialize memory in-place.
+///
+/// `T` is either a slice or a struct whose last field is a slice. The allocation is sized for a
+/// trailing slice of `len` elements and the initializer is given a pointer that carries `len` as
+/// its length, so the initializer of the trailing slice can initialize every element, for example
+/// using [`init_slice_from_fn`](crate::init_slice_from_fn). Initializers of structs with a trailing
+/// slice need the `#[dst]` attribute, see [`pin_init!`](crate::pin_init!).
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use core::pin::Pin;
+/// use pin_init::*;
+///
+/// #[pin_data]
+/// struct Packet {
+///     id: u32,
+///     data: [u8],
+/// }
+///
+/// fn packet(id: u32, payload: &[u8]) -> Pin<Box<Packet>> {
+///     let len = payload.len();
+///     let init = pin_init!(#[dst] Packet {
+///         id,
+///         data <- init_slice_from_fn(len, |i| payload[i]),
+///     });
+///     Box::pin_init_dst(len, init).unwrap()
+/// }
+///
+/// let packet = packet(7, b"hello");
+/// assert_eq!(packet.id, 7);
+/// assert_eq!(&packet.data, b"hello");
+/// ```
+#[cfg(feature = "dst")]
+pub trait InPlaceInitDst<T: ?Sized + Pointee<Metadata = usize>>: Sized {
+    /// Use the given pin-initializer to pin-initialize a `T` with a trailing slice of `len`
+    /// elements inside of a new smart pointer of this type.
+    fn try_pin_init_dst<E>(len: usize, init: impl PinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        E: From<AllocError>;
+
+    /// Use the given pin-initializer to pin-initialize a `T` with a trailing slice of `len`
+    /// elements inside of a new smart pointer of this type.
+    fn pin_init_dst(len: usize, init: impl PinInit<T>) -> Result<Pin<Self>, AllocError> {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            pin_init_from_closure(|slot| match init.__pinned_init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        Self::try_pin_init_dst(len, init)
+    }
+
+    /// Use the given initializer to in-place initialize a `T` with a trailing slice of `len`
+    /// elements.
+    fn try_init_dst<E>(len: usize, init: impl Init<T, E>) -> Result<Self, E>
+    where
+        E: From<AllocError>;
+
+    /// Use the given initializer to in-place initialize a `T` with a trailing slice of `len`
+    /// elements.
+    fn init_dst(len: usize, init: impl Init<T>) -> Result<Self, AllocError> {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            init_from_closure(|slot| match init.__init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        Self::try_init_dst(len, init)
+    }
+}
+
+/// Computes the layout of a `T` with a trailing slice of `len` elements.
+///
+/// Returns `None` if the size would not fit into an `isize`.
+#[cfg(feature = "dst")]
+fn dst_layout<T: ?Sized + Pointee<Metadata = usize>>(len: usize) -> Option<Layout> {
+    let raw = |len| ptr::from_raw_parts::<T>(ptr::null::<()>(), len);
+    // SAFETY: With an empty trailing slice, only the statically sized prefix remains, the size of
+    // which fits into an `isize`.
+    let empty = unsafe { Layout::for_value_raw(raw(0)) };
+    if len == 0 {
+        return Some(empty);
+    }
+    // SAFETY: The prefix and the element type each have a size that fits into an `isize`. The
+    // compiler rejects types for which the prefix followed by a single element does not.
+    let single = unsafe { Layout::for_value_raw(raw(1)) };
+    // The tail starts at an offset of at least `empty.size() - (align - 1)` and the size of `T`
+    // is rounded up to the alignment, so this is an upper bound for the size of an element.
+    let align = empty.align();
+    let element = single.size() - empty.size() + (align - 1);
+    let bound = element
+        .checked_mul(len)?
+        .checked_add(empty.size())?
+        .checked_add(align - 1)?;
+    if bound > isize::MAX as usize {
+        return None;
+    }
+    // SAFETY: The size of `T` with `len` elements is at most `bound`, which fits into an `isize`.
+    Some(unsafe { Layout::for_value_raw(raw(len)) })
+}
+
+/// Allocates a `T` with a trailing slice of `len` elements and initializes it using `init`.
+///
+/// # Safety
+///
+/// `init` has to fully initialize the given slot when it returns `Ok`. When it returns `Err` or
+/// panics, the slot has to be left uninitialized.
+#[cfg(feature = "dst")]
+unsafe fn try_new_dst<T, E>(
+    len: usize,
+    init: impl FnOnce(*mut T) -> Result<(), E>,
+) -> Result<Box<T>, E>
+where
+    T: ?Sized + Pointee<Metadata = usize>,
+    E: From<AllocError>,
+{
+    /// Deallocates the memory on drop.
+    struct Dealloc(ptr::NonNull<u8>, Layout);
+
+    impl Drop for Dealloc {
+        fn drop(&mut self) {
+            // SAFETY: The memory was allocated by `Global` with this layout and does not contain
+            // an initialized value.
+            unsafe { Global.deallocate(self.0, self.1) };
+        }
+    }
+
+    let layout = dst_layout::<T>(len).ok_or(AllocError)?;
+    let mem = Global.allocate(layout)?.cast::<u8>();
+    let guard = Dealloc(mem, layout);
+    let slot = ptr::from_raw_parts_mut::<T>(mem.as_ptr().cast::<()>(), len);
+    init(slot)?;
+    core::mem::forget(guard);
+    // SAFETY: `slot` was allocated by `Global` with the layout of the `T` pointed to by it, which
+    // has been fully initialized by `init`.
+    Ok(unsafe { Box::from_raw(slot) })
+}
+
+#[cfg(feature = "dst")]
+impl<T: ?Sized + Pointee<Metadata = usize>> InPlaceInitDst<T> for Box<T> {
+    #[inline]
+    fn try_pin_init_dst<E>(len: usize, init: impl PinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        E: From<AllocError>,
+    {
+        // SAFETY: `slot` is valid for writes of a `T` with `len` elements and will not be moved,
+        // because we pin it afterwards. The other requirements follow from `PinInit`.
+        let this = unsafe { try_new_dst(len, |slot| poison::checked(init).__pinned_init(slot))? };
+        Ok(Box::into_pin(this))
+    }
+
+    #[inline]
+    fn try_init_dst<E>(len: usize, init: impl Init<T, E>) -> Result<Self, E>
+    where
+        E: From<AllocError>,
+    {
+        // SAFETY: `slot` is valid for writes of a `T` with `len` elements. The other requirements
+        // follow from `Init`.
+        unsafe { try_new_dst(len, |slot| poison::checked_init(init).__init(slot)) }
+    }
+}
+
 #[cfg(feature = "alloc")]
 macro_rules! try_new_uninit {
     ($type:ident) => {
@@ -109,7 +372,7 @@ impl<T> InPlaceInit<T> for Arc<T> {
         let slot = slot.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid and will not be moved, because we pin it later.
-        unsafe { init.__pinned_init(slot)? };
+        unsafe { poison::checked(init).__pinned_init(slot)? };
         // SAFETY: All fields have been initialized and this is the only `Arc` to that data.
         Ok(unsafe { Pin::new_unchecked(this.assume_init()) })
     }
@@ -127,12 +390,54 @@ impl<T> InPlaceInit<T> for Arc<T> {
         let slot = slot.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid.
-        unsafe { init.__init(slot)? };
+        unsafe { poison::checked_init(init).__init(slot)? };
         // SAFETY: All fields have been initialized.
         Ok(unsafe { this.assume_init() })
     }
 }
 
+#[cfg(feature = "dst")]
+impl<U: ?Sized> InPlaceInitUnsized<U> for Box<U> {
+    #[inline]
+    fn try_pin_init_unsized<T, E>(init: impl PinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>,
+    {
+        Ok(Box::try_pin_init(init)?)
+    }
+
+    #[inline]
+    fn try_init_unsized<T, E>(init: impl Init<T, E>) -> Result<Self, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>,
+    {
+        Ok(Box::try_init(init)?)
+    }
+}
+
+#[cfg(feature = "dst")]
+impl<U: ?Sized> InPlaceInitUnsized<U> for Arc<U> {
+    #[inline]
+    fn try_pin_init_unsized<T, E>(init: impl PinInit<T, E>) -> Result<Pin<Self>, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>,
+    {
+        Ok(Arc::try_pin_init(init)?)
+    }
+
+    #[inline]
+    fn try_init_unsized<T, E>(init: impl Init<T, E>) -> Result<Self, E>
+    where
+        T: Unsize<U>,
+        E: From<AllocError>,
+    {
+        Ok(Arc::try_init(init)?)
+    }
+}
+
 impl<T> InPlaceWrite<T> for Box<MaybeUninit<T>> {
     type Initialized = Box<T>;
 
@@ -140,7 +445,7 @@ impl<T> InPlaceWrite<T> for Box<MaybeUninit<T>> {
         let slot = self.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid.
-        unsafe { init.__init(slot)? };
+        unsafe { poison::checked_init(init).__init(slot)? };
         // SAFETY: All fields have been initialized.
         Ok(unsafe { self.assume_init() })
     }
@@ -149,8 +454,597 @@ impl<T> InPlaceWrite<T> for Box<MaybeUninit<T>> {
         let slot = self.as_mut_ptr();
         // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
         // slot is valid and will not be moved, because we pin it later.
-        unsafe { init.__pinned_init(slot)? };
+        unsafe { poison::checked(init).__pinned_init(slot)? };
         // SAFETY: All fields have been initialized.
         Ok(unsafe { self.assume_init() }.into())
     }
 }
+
+/// A uniquely owned [`Arc`] or [`Rc`].
+///
+/// [`Unique::new`] checks that there are no other strong or weak pointers to the allocation. Since
+/// the `Unique` owns the pointer and does not hand out references to it, the pointer stays unique,
+/// so `Unique<Arc<MaybeUninit<T>>>` and `Unique<Rc<MaybeUninit<T>>>` implement [`InPlaceWrite`].
+///
+/// # Examples
+///
+/// ```rust
+/// use core::convert::Infallible;
+/// use pin_init::*;
+/// use std::sync::Arc;
+///
+/// let arc = Arc::<u32>::new_uninit();
+/// let shared = arc.clone();
+/// let arc = Unique::new(arc).err().unwrap();
+/// drop(shared);
+/// let arc = Unique::new(arc).ok().unwrap();
+/// let arc = arc.write_init(Ok::<_, Infallible>(42)).unwrap();
+/// assert_eq!(*arc, 42);
+/// ```
+pub struct Unique<P>(P);
+
+mod sealed {
+    /// Reference counted pointers that can be checked for uniqueness.
+    pub trait RefCounted {
+        /// Returns `true` if there are no other strong or weak pointers to the allocation.
+        fn is_unique(&mut self) -> bool;
+    }
+}
+
+impl<T: ?Sized> sealed::RefCounted for Arc<T> {
+    #[inline]
+    fn is_unique(&mut self) -> bool {
+        Arc::get_mut(self).is_some()
+    }
+}
+
+impl<T: ?Sized> sealed::RefCounted for Rc<T> {
+    #[inline]
+    fn is_unique(&mut self) -> bool {
+        Rc::get_mut(self).is_some()
+    }
+}
+
+impl<P: sealed::RefCounted> Unique<P> {
+    /// Checks that there are no other strong or weak pointers to the allocation of `ptr`.
+    ///
+    /// Returns `ptr` if it is shared.
+    #[inline]
+    pub fn new(mut ptr: P) -> Result<Self, P> {
+        if ptr.is_unique() {
+            Ok(Self(ptr))
+        } else {
+            Err(ptr)
+        }
+    }
+}
+
+impl<P> Unique<P> {
+    /// Returns the pointer.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<T> InPlaceWrite<T> for Unique<Arc<MaybeUninit<T>>> {
+    type Initialized = Arc<T>;
+
+    fn write_init<E>(mut self, init: impl Init<T, E>) -> Result<Self::Initialized, E> {
+        let Some(slot) = Arc::get_mut(&mut self.0) else {
+            // SAFETY: The `Arc` was unique when the `Unique` was created and cannot have been
+            // shared since.
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let slot = slot.as_mut_ptr();
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid and no other pointer can access it.
+        unsafe { poison::checked_init(init).__init(slot)? };
+        // SAFETY: All fields have been initialized.
+        Ok(unsafe { self.0.assume_init() })
+    }
+
+    fn write_pin_init<E>(mut self, init: impl PinInit<T, E>) -> Result<Pin<Self::Initialized>, E> {
+        let Some(slot) = Arc::get_mut(&mut self.0) else {
+            // SAFETY: The `Arc` was unique when the `Unique` was created and cannot have been
+            // shared since.
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let slot = slot.as_mut_ptr();
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid and will not be moved, because we pin it later.
+        unsafe { poison::checked(init).__pinned_init(slot)? };
+        // SAFETY: All fields have been initialized. The contents of an `Arc` are never moved.
+        Ok(unsafe { Pin::new_unchecked(self.0.assume_init()) })
+    }
+}
+
+impl<T> InPlaceWrite<T> for Unique<Rc<MaybeUninit<T>>> {
+    type Initialized = Rc<T>;
+
+    fn write_init<E>(mut self, init: impl Init<T, E>) -> Result<Self::Initialized, E> {
+        let Some(slot) = Rc::get_mut(&mut self.0) else {
+            // SAFETY: The `Rc` was unique when the `Unique` was created and cannot have been
+            // shared since.
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let slot = slot.as_mut_ptr();
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid and no other pointer can access it.
+        unsafe { poison::checked_init(init).__init(slot)? };
+        // SAFETY: All fields have been initialized.
+        Ok(unsafe { self.0.assume_init() })
+    }
+
+    fn write_pin_init<E>(mut self, init: impl PinInit<T, E>) -> Result<Pin<Self::Initialized>, E> {
+        let Some(slot) = Rc::get_mut(&mut self.0) else {
+            // SAFETY: The `Rc` was unique when the `Unique` was created and cannot have been
+            // shared since.
+            unsafe { core::hint::unreachable_unchecked() }
+        };
+        let slot = slot.as_mut_ptr();
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid and will not be moved, because we pin it later.
+        unsafe { poison::checked(init).__pinned_init(slot)? };
+        // SAFETY: All fields have been initialized. The contents of an `Rc` are never moved.
+        Ok(unsafe { Pin::new_unchecked(self.0.assume_init()) })
+    }
+}
+
+/// Smart pointer to uninitialized elements that can be initialized in-place.
+///
+/// This is the slice counterpart of [`InPlaceWrite`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::{convert::Infallible, pin::Pin};
+/// use pin_init::*;
+///
+/// let uninit = Box::new_uninit_slice(16);
+/// let mutexes: Pin<Box<[CMutex<usize>]>> =
+///     uninit.write_pin_init_slice(CMutex::new).unwrap_or_else(|e: Infallible| match e {});
+/// assert_eq!(*mutexes[15].lock(), 15);
+/// ```
+pub trait InPlaceWriteSlice<T> {
+    /// The type `Self` turns into when the elements are initialized.
+    type Initialized;
+
+    /// Initializes every element with the initializer returned by `make_init` for its index.
+    ///
+    /// Does not drop the current elements and considers them as uninitialized memory. When an
+    /// initializer fails, the elements initialized before it are dropped.
+    fn write_init_slice<I, E>(
+        self,
+        make_init: impl FnMut(usize) -> I,
+    ) -> Result<Self::Initialized, E>
+    where
+        I: Init<T, E>;
+
+    /// Pin-initializes every element with the initializer returned by `make_init` for its index.
+    ///
+    /// Does not drop the current elements and considers them as uninitialized memory. When an
+    /// initializer fails, the elements initialized before it are dropped.
+    fn write_pin_init_slice<I, E>(
+        self,
+        make_init: impl FnMut(usize) -> I,
+    ) -> Result<Pin<Self::Initialized>, E>
+    where
+        I: PinInit<T, E>;
+}
+
+impl<T> InPlaceWriteSlice<T> for Box<[MaybeUninit<T>]> {
+    type Initialized = Box<[T]>;
+
+    fn write_init_slice<I, E>(
+        mut self,
+        make_init: impl FnMut(usize) -> I,
+    ) -> Result<Self::Initialized, E>
+    where
+        I: Init<T, E>,
+    {
+        let slot = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().cast::<T>(), self.len());
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid.
+        unsafe { init_slice_from_fn(slot.len(), make_init).__init(slot)? };
+        // SAFETY: All elements have been initialized.
+        Ok(unsafe { self.assume_init() })
+    }
+
+    fn write_pin_init_slice<I, E>(
+        mut self,
+        make_init: impl FnMut(usize) -> I,
+    ) -> Result<Pin<Self::Initialized>, E>
+    where
+        I: PinInit<T, E>,
+    {
+        let slot = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().cast::<T>(), self.len());
+        // SAFETY: When init errors/panics, slot will get deallocated but not dropped,
+        // slot is valid and will not be moved, because we pin it later.
+        unsafe { pin_init_slice_from_fn(slot.len(), make_init).__pinned_init(slot)? };
+        // SAFETY: All elements have been initialized.
+        Ok(unsafe { self.assume_init() }.into())
+    }
+}
+
+/// Collection that can initialize new elements in-place.
+///
+/// The new elements are initialized directly in the spare capacity of the collection, so they are
+/// never built on the stack. The length of the collection is only increased after all new elements
+/// have been initialized successfully.
+///
+/// Memory is reserved fallibly. Without the `alloc` feature, `AllocError` is not available, so
+/// allocation failures are reported as [`TryReserveError`] instead.
+///
+/// [`TryReserveError`]: std::collections::TryReserveError
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use pin_init::*;
+///
+/// struct Record {
+///     id: usize,
+///     payload: [u8; 64 * 1024],
+/// }
+///
+/// fn record(id: usize) -> impl Init<Record> {
+///     init!(Record {
+///         id,
+///         payload <- init_zeroed(),
+///     })
+/// }
+///
+/// let mut queue = Vec::new();
+/// queue.push_init(record(0)).unwrap();
+/// queue.extend_init(3, |i| record(i + 1)).unwrap();
+/// assert_eq!(queue.len(), 4);
+/// assert_eq!(queue[3].id, 3);
+/// ```
+pub trait InPlaceExtend<T> {
+    /// Use the given initializer to in-place initialize a new element at the end of the
+    /// collection.
+    ///
+    /// On failure the collection is unchanged.
+    fn try_push_init<E>(&mut self, init: impl Init<T, E>) -> Result<(), E>
+    where
+        E: From<ReserveError>;
+
+    /// Use the given initializer to in-place initialize a new element at the end of the
+    /// collection.
+    fn push_init(&mut self, init: impl Init<T>) -> Result<(), ReserveError> {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            init_from_closure(|slot| match init.__init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        self.try_push_init(init)
+    }
+
+    /// Initializes `n` new elements at the end of the collection, the `i`th of which is
+    /// initialized by the initializer returned by `make_init(i)`.
+    ///
+    /// On failure, the new elements that have been initialized are dropped and the collection is
+    /// unchanged.
+    fn try_extend_init<I, E>(
+        &mut self,
+        n: usize,
+        make_init: impl FnMut(usize) -> I,
+    ) -> Result<(), E>
+    where
+        I: Init<T, E>,
+        E: From<ReserveError>;
+
+    /// Initializes `n` new elements at the end of the collection, the `i`th of which is
+    /// initialized by the initializer returned by `make_init(i)`.
+    fn extend_init<I>(
+        &mut self,
+        n: usize,
+        mut make_init: impl FnMut(usize) -> I,
+    ) -> Result<(), ReserveError>
+    where
+        I: Init<T>,
+    {
+        self.try_extend_init(n, |i| {
+            let init = make_init(i);
+            // SAFETY: We delegate to `init` and only change the error type.
+            unsafe {
+                init_from_closure(|slot| match init.__init(slot) {
+                    Ok(()) => Ok(()),
+                    Err(i) => match i {},
+                })
+            }
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! try_reserve {
+    ($vec:expr, $n:expr) => {
+        $vec.try_reserve($n).map_err(|_| AllocError)?
+    };
+}
+#[cfg(all(feature = "std", not(feature = "alloc")))]
+macro_rules! try_reserve {
+    ($vec:expr, $n:expr) => {
+        $vec.try_reserve($n)?
+    };
+}
+
+impl<T> InPlaceExtend<T> for Vec<T> {
+    fn try_push_init<E>(&mut self, init: impl Init<T, E>) -> Result<(), E>
+    where
+        E: From<ReserveError>,
+    {
+        try_reserve!(self, 1);
+        let len = self.len();
+        // SAFETY: We reserved space for one more element.
+        let slot = unsafe { self.as_mut_ptr().add(len) };
+        // SAFETY: `slot` is valid for writes and uninitialized. When init errors/panics, the
+        // length is not increased, so the slot is not dropped.
+        unsafe { init.__init(slot)? };
+        // SAFETY: The element at `len` has been initialized and is within the capacity.
+        unsafe { self.set_len(len + 1) };
+        Ok(())
+    }
+
+    fn try_extend_init<I, E>(
+        &mut self,
+        n: usize,
+        mut make_init: impl FnMut(usize) -> I,
+    ) -> Result<(), E>
+    where
+        I: Init<T, E>,
+        E: From<ReserveError>,
+    {
+        try_reserve!(self, n);
+        let len = self.len();
+        let spare = &mut self.spare_capacity_mut()[..n];
+        let slot = ptr::slice_from_raw_parts_mut(spare.as_mut_ptr().cast::<T>(), n);
+        // SAFETY: `slot` is part of the spare capacity and thus valid for writes and
+        // uninitialized. The elements are only initialized with `Init`.
+        let mut builder = unsafe { SliceBuilder::new(slot) };
+        for i in 0..n {
+            builder.push_init(make_init(i)).map_err(|(_, err)| err)?;
+        }
+        builder.finish();
+        // SAFETY: The `n` elements after `len` have been initialized and are within the capacity.
+        unsafe { self.set_len(len + n) };
+        Ok(())
+    }
+}
+
+/// Object safe version of [`PinInit`].
+///
+/// [`PinInit`] consumes `self` and therefore cannot be used as a trait object. This trait is
+/// implemented for every [`PinInit`] and takes `self` in a [`Box`] instead, so
+/// `Box<dyn DynPinInit<T, E>>` can be used to store initializers of different types. Usually this
+/// trait is used through [`BoxedPinInit`].
+///
+/// # Safety
+///
+/// The same as for [`PinInit`].
+pub unsafe trait DynPinInit<T: ?Sized, E> {
+    /// Initializes `slot` with the boxed initializer.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`PinInit::__pinned_init`].
+    unsafe fn __pinned_init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E>;
+}
+
+// SAFETY: Delegates to `PinInit::__pinned_init` with the same safety requirements.
+unsafe impl<T: ?Sized, E, I: PinInit<T, E>> DynPinInit<T, E> for I {
+    #[inline]
+    unsafe fn __pinned_init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { (*self).__pinned_init(slot) }
+    }
+}
+
+/// Object safe version of [`Init`].
+///
+/// See [`DynPinInit`] for more information. Usually this trait is used through [`BoxedInit`].
+///
+/// # Safety
+///
+/// The same as for [`Init`].
+pub unsafe trait DynInit<T: ?Sized, E> {
+    /// Initializes `slot` with the boxed initializer.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`Init::__init`].
+    unsafe fn __init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E>;
+}
+
+// SAFETY: Delegates to `Init::__init` with the same safety requirements.
+unsafe impl<T: ?Sized, E, I: Init<T, E>> DynInit<T, E> for I {
+    #[inline]
+    unsafe fn __init_boxed(self: Box<Self>, slot: *mut T) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { (*self).__init(slot) }
+    }
+}
+
+/// A type-erased pin-initializer.
+///
+/// Stores any [`PinInit<T, E>`] in a [`Box`], so initializers of different types can be kept in
+/// the same collection or passed through non-generic APIs. `BoxedPinInit` itself implements
+/// [`PinInit<T, E>`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use core::pin::Pin;
+/// use pin_init::*;
+///
+/// let inits: Vec<BoxedPinInit<'_, CMutex<usize>>> = vec![
+///     BoxedPinInit::new(CMutex::new(0)),
+///     BoxedPinInit::new(pin_init_scope(|| Ok(CMutex::new(42)))),
+/// ];
+/// let mutexes: Vec<Pin<Box<CMutex<usize>>>> = inits
+///     .into_iter()
+///     .map(|init| Box::pin_init(init).unwrap())
+///     .collect();
+/// assert_eq!(*mutexes[1].lock(), 42);
+/// ```
+pub struct BoxedPinInit<'a, T: ?Sized, E = Infallible>(Box<dyn DynPinInit<T, E> + 'a>);
+
+impl<'a, T: ?Sized, E> BoxedPinInit<'a, T, E> {
+    /// Moves `init` into a new [`Box`] and erases its type.
+    #[inline]
+    pub fn new(init: impl PinInit<T, E> + 'a) -> Self {
+        Self(Box::new(init))
+    }
+}
+
+impl<'a, T: ?Sized, E> From<Box<dyn DynPinInit<T, E> + 'a>> for BoxedPinInit<'a, T, E> {
+    #[inline]
+    fn from(init: Box<dyn DynPinInit<T, E> + 'a>) -> Self {
+        Self(init)
+    }
+}
+
+// SAFETY: Delegates to the boxed initializer.
+unsafe impl<T: ?Sized, E> PinInit<T, E> for BoxedPinInit<'_, T, E> {
+    #[inline]
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.0.__pinned_init_boxed(slot) }
+    }
+}
+
+/// A type-erased initializer.
+///
+/// The [`Init`] version of [`BoxedPinInit`].
+pub struct BoxedInit<'a, T: ?Sized, E = Infallible>(Box<dyn DynInit<T, E> + 'a>);
+
+impl<'a, T: ?Sized, E> BoxedInit<'a, T, E> {
+    /// Moves `init` into a new [`Box`] and erases its type.
+    #[inline]
+    pub fn new(init: impl Init<T, E> + 'a) -> Self {
+        Self(Box::new(init))
+    }
+}
+
+impl<'a, T: ?Sized, E> From<Box<dyn DynInit<T, E> + 'a>> for BoxedInit<'a, T, E> {
+    #[inline]
+    fn from(init: Box<dyn DynInit<T, E> + 'a>) -> Self {
+        Self(init)
+    }
+}
+
+// SAFETY: Delegates to the boxed initializer.
+unsafe impl<T: ?Sized, E> Init<T, E> for BoxedInit<'_, T, E> {
+    #[inline]
+    unsafe fn __init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.0.__init_boxed(slot) }
+    }
+}
+
+// SAFETY: Delegates to the boxed initializer, `Init` is also a valid `PinInit`.
+unsafe impl<T: ?Sized, E> PinInit<T, E> for BoxedInit<'_, T, E> {
+    #[inline]
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: Per the safety requirements of this function.
+        unsafe { self.0.__init_boxed(slot) }
+    }
+}
+
+/// A pending pin-initialization in a new [`Box`], where the type of the value is erased to `U`.
+///
+/// Where [`BoxedPinInit`] erases the type of the initializer, this type also erases the type of
+/// the initialized value. It is created from an initializer of some type `T` and a function
+/// converting `Pin<Box<T>>` to `Pin<Box<U>>`, usually an unsizing coercion to a trait object.
+/// The allocation only happens in [`BoxedDynPinInit::pin_init`]. Use
+/// [`BoxedDynPinInit::try_new`] for fallible initializers.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::pin::Pin;
+/// use pin_init::*;
+///
+/// trait Plugin {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// #[pin_data]
+/// struct Logger {
+///     level: u8,
+/// }
+///
+/// impl Plugin for Logger {
+///     fn name(&self) -> &'static str {
+///         "logger"
+///     }
+/// }
+///
+/// #[pin_data]
+/// struct Metrics {
+///     interval: u32,
+/// }
+///
+/// impl Plugin for Metrics {
+///     fn name(&self) -> &'static str {
+///         "metrics"
+///     }
+/// }
+///
+/// let pending: Vec<BoxedDynPinInit<'_, dyn Plugin>> = vec![
+///     BoxedDynPinInit::new(pin_init!(Logger { level: 3 }), |b| b),
+///     BoxedDynPinInit::new(pin_init!(Metrics { interval: 10 }), |b| b),
+/// ];
+/// let plugins: Vec<Pin<Box<dyn Plugin>>> = pending
+///     .into_iter()
+///     .map(|init| init.pin_init().unwrap())
+///     .collect();
+/// assert_eq!(plugins[1].name(), "metrics");
+/// ```
+pub struct BoxedDynPinInit<'a, U: ?Sized, E = AllocError>(
+    Box<dyn FnOnce() -> Result<Pin<Box<U>>, E> + 'a>,
+);
+
+impl<'a, U: ?Sized + 'a> BoxedDynPinInit<'a, U, AllocError> {
+    /// Creates a pending pin-initialization of a `T` that is converted to `U` with `coerce`.
+    #[inline]
+    pub fn new<T: 'a>(init: impl PinInit<T> + 'a, coerce: fn(Pin<Box<T>>) -> Pin<Box<U>>) -> Self {
+        // SAFETY: We delegate to `init` and only change the error type.
+        let init = unsafe {
+            pin_init_from_closure(|slot| match init.__pinned_init(slot) {
+                Ok(()) => Ok(()),
+                Err(i) => match i {},
+            })
+        };
+        Self::try_new(init, coerce)
+    }
+}
+
+impl<'a, U: ?Sized + 'a, E: 'a> BoxedDynPinInit<'a, U, E> {
+    /// Creates a pending pin-initialization of a `T` that is converted to `U` with `coerce`.
+    #[inline]
+    pub fn try_new<T: 'a>(
+        init: impl PinInit<T, E> + 'a,
+        coerce: fn(Pin<Box<T>>) -> Pin<Box<U>>,
+    ) -> Self
+    where
+        E: From<AllocError>,
+    {
+        Self(Box::new(move || Box::try_pin_init(init).map(coerce)))
+    }
+
+    /// Allocates a new [`Box`] and pin-initializes the value inside of it.
+    #[inline]
+    pub fn pin_init(self) -> Result<Pin<Box<U>>, E> {
+        (self.0)()
+    }
+}
@@ -32,12 +32,34 @@
 //! will require the `std` feature, because stable compilers have neither `Box` nor `Arc` in no-std
 //! mode.
 //!
+//! ## Nightly needed for `dst` feature
+//!
+//! This feature enables [`InPlaceInitUnsized`] and [`InPlaceInitDst`] to allocate dynamically sized
+//! types such as trait objects, slices and structs with a trailing slice. It implies the `alloc`
+//! feature and additionally requires the `layout_for_ptr`, `ptr_metadata` and `unsize` unstable
+//! features, which the crate enables itself. Note that this feature is not enabled by default.
+//!
 //! ## Nightly needed for `unsafe-pinned` feature
 //!
 //! This feature enables the `Wrapper` implementation on the unstable `core::pin::UnsafePinned` type.
 //! This requires the [`unsafe_pinned` unstable feature](https://github.com/rust-lang/rust/issues/125735)
 //! and therefore a nightly compiler. Note that this feature is not enabled by default.
 //!
+//! ## Nightly needed for `debug-poison` feature
+//!
+//! This feature fills the memory of [`Box<T>`], [`Arc<T>`], [`stack_pin_init!`] and
+//! [`InPlaceWrite`] slots, as well as of the fields initialized with `<-` in [`pin_init!`] and
+//! [`init!`], with the byte `0xA5` before running the initializer. After the initializer succeeded,
+//! the slot is checked if its type uses [`pin_data`] or derives [`Zeroable`]: if one of its fields
+//! still only contains `0xA5`, the initializer has not written it and a panic names the type and the
+//! field. Only fields of primitive, pointer and reference types, or arrays of them, that are at
+//! least two bytes long are checked, since other fields may contain uninitialized bytes.
+//!
+//! The feature is meant for debugging hand-written initializers. It requires the
+//! [`min_specialization` unstable feature](https://github.com/rust-lang/rust/issues/31844) and
+//! therefore a nightly compiler. Users do not need to enable it themselves. Note that this feature
+//! is not enabled by default.
+//!
 //! # Overview
 //!
 //! To initialize a `struct` with an in-place constructor you will need two things:
@@ -264,10 +286,16 @@
 #![forbid(missing_docs, unsafe_op_in_unsafe_fn)]
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg_attr(feature = "dst", feature(layout_for_ptr, ptr_metadata, unsize))]
 #![cfg_attr(
     all(feature = "unsafe-pinned", CONFIG_RUSTC_HAS_UNSAFE_PINNED),
     feature(unsafe_pinned)
 )]
+#![cfg_attr(
+    feature = "debug-poison",
+    feature(min_specialization, rustc_attrs, allow_internal_unstable)
+)]
+#![cfg_attr(feature = "debug-poison", allow(internal_features))]
 #![cfg_attr(all(USE_RUSTC_FEATURES, doc), allow(internal_features))]
 #![cfg_attr(all(USE_RUSTC_FEATURES, doc), feature(rustdoc_internals))]
 
@@ -275,8 +303,9 @@ use core::{
     cell::UnsafeCell,
     convert::Infallible,
     marker::PhantomData,
-    mem::MaybeUninit,
+    mem::{ManuallyDrop, MaybeUninit},
     num::*,
+    ops::{Deref, DerefMut},
     pin::Pin,
     ptr::{self, NonNull},
 };
@@ -289,10 +318,31 @@ extern crate self as pin_init;
 #[doc(hidden)]
 pub mod __internal;
 
+mod poison;
+
 #[cfg(any(feature = "std", feature = "alloc"))]
 mod alloc;
 #[cfg(any(feature = "std", feature = "alloc"))]
-pub use alloc::InPlaceInit;
+pub use alloc::{
+    BoxedDynPinInit, BoxedInit, BoxedPinInit, DynInit, DynPinInit, InPlaceExtend, InPlaceInit,
+    InPlaceWriteSlice, Unique,
+};
+#[cfg(feature = "dst")]
+pub use alloc::{InPlaceInitDst, InPlaceInitUnsized};
+
+#[cfg(feature = "std")]
+mod par;
+#[cfg(feature = "std")]
+pub use par::{
+    par_init_array_from_fn, par_init_slice_from_fn, par_pin_init_array_from_fn,
+    par_pin_init_slice_from_fn,
+};
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "trace")]
+pub mod trace;
 
 /// Used to specify the pinning information of the fields of a struct.
 ///
@@ -308,6 +358,43 @@ pub use alloc::InPlaceInit;
 /// macro, and change your `Drop` implementation to `PinnedDrop` annotated with
 /// `#[`[`macro@pinned_drop`]`]`, since dropping pinned values requires extra care.
 ///
+/// With the `new` argument, a `new` function is generated that returns a pin-initializer for the
+/// struct. It has a parameter of the same name for every field, taking an `impl PinInit<F, E>` for
+/// fields with `#[pin]` and an `impl Init<F, E>` for all others, where `F` is the type of the field
+/// and `E` the error type of the returned initializer. This can be adjusted for every field:
+/// - `#[new(value)]` takes the field by value instead,
+/// - `#[new(default)]` initializes the field with [`Default::default`] instead of taking a
+///   parameter,
+/// - `#[new(default = expr)]` initializes the field with `expr` instead of taking a parameter.
+///
+/// Fields of type [`PhantomPinned`](core::marker::PhantomPinned) never take a parameter.
+///
+/// With the `builder` argument, a `pin_init_builder` function is generated instead, returning a
+/// builder with a setter for every field that takes the same parameter as `new` would. Fields with
+/// `#[new(default)]` or `#[new(default = expr)]` have an optional setter overriding the default.
+/// Every setter can be called only once and `build`, which returns the pin-initializer, only exists
+/// after all required setters have been called. The builder does not allocate and does not support
+/// fields with `#[cfg]`.
+///
+/// Structs defined in other crates, such as bindings generated by bindgen, can be initialized by
+/// declaring a mirror of them with `unsafe remote = path::Foo`. The mirror lists all fields of the
+/// remote struct with the same names and types and marks the structurally pinned ones with
+/// `#[pin]`. This is checked at compile time, as is that the remote struct does not implement
+/// `Drop` and, if it has structurally pinned fields, `Unpin`. The offsets of the fields and the
+/// size are always compared with the remote struct, so a mirror of a `#[repr(C)]` struct should be
+/// `#[repr(C)]` too, otherwise it only compiles if the compiler happens to choose the same layout.
+/// [`pin_init!`] then returns an initializer of the remote struct when given the mirror, which
+/// can't be combined with `PinnedDrop`, `new` or `builder`. Since [`init!`] only needs the fields
+/// to be accessible, it can be used on the remote struct directly.
+///
+/// Arguments are separated by commas, e.g. `#[pin_data(PinnedDrop, new)]`.
+///
+/// # Safety
+///
+/// With `unsafe remote = path::Foo`, the caller guarantees that the remote struct treats the
+/// fields marked with `#[pin]` as structurally pinned, i.e. that the crate defining it never moves
+/// them out of a pinned remote struct, for example by giving out `&mut` references to them.
+///
 /// # Examples
 ///
 /// ```
@@ -353,6 +440,83 @@ pub use alloc::InPlaceInit;
 ///     }
 /// }
 /// ```
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::{convert::Infallible, marker::PhantomPinned};
+/// use pin_init::{pin_data, stack_pin_init};
+///
+/// #[pin_data(new)]
+/// struct Device {
+///     #[pin]
+///     state: CMutex<u32>,
+///     #[new(value)]
+///     name: &'static str,
+///     #[new(default)]
+///     opened: usize,
+///     #[pin]
+///     _pin: PhantomPinned,
+/// }
+///
+/// // `Device::new` has the signature
+/// // `fn new<E>(state: impl PinInit<CMutex<u32>, E>, name: &'static str) -> impl PinInit<Self, E>`.
+/// stack_pin_init!(let device = Device::new::<Infallible>(CMutex::new(0), "uart"));
+/// assert_eq!(device.name, "uart");
+/// ```
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::convert::Infallible;
+/// use pin_init::{pin_data, stack_pin_init};
+///
+/// #[pin_data(builder)]
+/// struct Device {
+///     #[pin]
+///     state: CMutex<u32>,
+///     #[new(value)]
+///     irq: u32,
+///     #[new(default = 16)]
+///     queue_len: usize,
+/// }
+///
+/// let init = Device::pin_init_builder::<Infallible>()
+///     .irq(5)
+///     .state(CMutex::new(0))
+///     .build();
+/// stack_pin_init!(let device = init);
+/// assert_eq!((device.irq, device.queue_len), (5, 16));
+/// ```
+///
+/// ```
+/// use core::{convert::Infallible, marker::PhantomPinned};
+/// use pin_init::{pin_data, pin_init, stack_pin_init};
+///
+/// // Generated by bindgen in another crate.
+/// mod bindings {
+///     #[repr(C)]
+///     pub struct queue {
+///         pub len: usize,
+///         pub _pin: core::marker::PhantomPinned,
+///     }
+/// }
+///
+/// // The bindings never move `_pin` out of a pinned `queue`.
+/// #[pin_data(unsafe remote = bindings::queue)]
+/// #[repr(C)]
+/// struct Queue {
+///     len: usize,
+///     #[pin]
+///     _pin: PhantomPinned,
+/// }
+///
+/// stack_pin_init!(let queue: bindings::queue = pin_init!(Queue {
+///     len: 0,
+///     _pin: PhantomPinned,
+/// }? Infallible));
+/// assert_eq!(queue.len, 0);
+/// ```
 pub use ::pin_init_internal::pin_data;
 
 /// Used to implement `PinnedDrop` safely.
@@ -480,14 +644,44 @@ pub use ::pin_init_internal::MaybeZeroable;
 /// println!("a: {}", &*foo.a.lock());
 /// ```
 ///
+/// Several values can be pinned in one invocation, later ones can borrow earlier ones:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pin_init::*;
+/// stack_pin_init!(
+///     let limit = CMutex::new(10),
+///     counters: [CMutex<usize>; 3] = |i| CMutex::new(i * *limit.lock()),
+/// );
+/// assert_eq!(*counters[2].lock(), 20);
+/// let second = pin_slice_get_mut(counters.as_mut(), 1).unwrap();
+/// assert_eq!(*second.lock(), 10);
+/// ```
+///
 /// # Syntax
 ///
 /// A normal `let` binding with optional type annotation. The expression is expected to implement
 /// [`PinInit`]/[`Init`] with the error type [`Infallible`]. If you want to use a different error
 /// type, then use [`stack_try_pin_init!`].
+///
+/// Multiple bindings are separated by commas, only the first one starts with `let`. Each binding is
+/// initialized in order and can refer to the earlier ones.
+///
+/// A binding with an array type `[T; N]` can be given a closure `|i| ...` returning the initializer
+/// of the element at index `i` instead of an expression. The binding then has the type
+/// `Pin<&mut [T; N]>`, use [`pin_slice_get_mut`] and [`pin_slice_iter_mut`] to access the pinned
+/// elements.
 #[macro_export]
 macro_rules! stack_pin_init {
-    (let $var:ident $(: $t:ty)? = $val:expr) => {
+    (let) => {};
+    (let $var:ident: [$t:ty; $n:tt] = |$i:pat_param| $body:expr $(, $($rest:tt)*)?) => {
+        $crate::stack_pin_init!(
+            let $var: [$t; $n] = $crate::pin_init_array_from_fn(|$i| $body)
+        );
+        $($crate::stack_pin_init!(let $($rest)*);)?
+    };
+    (let $var:ident $(: $t:ty)? = $val:expr $(, $($rest:tt)*)?) => {
         let val = $val;
         let mut $var = ::core::pin::pin!($crate::__internal::StackInit$(::<$t>)?::uninit());
         let mut $var = match $crate::__internal::StackInit::init($var, val) {
@@ -497,6 +691,7 @@ macro_rules! stack_pin_init {
                 match x {}
             }
         };
+        $($crate::stack_pin_init!(let $($rest)*);)?
     };
 }
 
@@ -561,17 +756,34 @@ macro_rules! stack_pin_init {
 /// A normal `let` binding with optional type annotation. The expression is expected to implement
 /// [`PinInit`]/[`Init`]. This macro assigns a result to the given variable, adding a `?` after the
 /// `=` will propagate this error.
+///
+/// Multiple bindings and array bindings are supported in the same way as in [`stack_pin_init!`].
 #[macro_export]
 macro_rules! stack_try_pin_init {
-    (let $var:ident $(: $t:ty)? = $val:expr) => {
+    (let) => {};
+    (let $var:ident: [$t:ty; $n:tt] = |$i:pat_param| $body:expr $(, $($rest:tt)*)?) => {
+        $crate::stack_try_pin_init!(
+            let $var: [$t; $n] = $crate::pin_init_array_from_fn(|$i| $body)
+        );
+        $($crate::stack_try_pin_init!(let $($rest)*);)?
+    };
+    (let $var:ident: [$t:ty; $n:tt] =? |$i:pat_param| $body:expr $(, $($rest:tt)*)?) => {
+        $crate::stack_try_pin_init!(
+            let $var: [$t; $n] =? $crate::pin_init_array_from_fn(|$i| $body)
+        );
+        $($crate::stack_try_pin_init!(let $($rest)*);)?
+    };
+    (let $var:ident $(: $t:ty)? = $val:expr $(, $($rest:tt)*)?) => {
         let val = $val;
         let mut $var = ::core::pin::pin!($crate::__internal::StackInit$(::<$t>)?::uninit());
         let mut $var = $crate::__internal::StackInit::init($var, val);
+        $($crate::stack_try_pin_init!(let $($rest)*);)?
     };
-    (let $var:ident $(: $t:ty)? =? $val:expr) => {
+    (let $var:ident $(: $t:ty)? =? $val:expr $(, $($rest:tt)*)?) => {
         let val = $val;
         let mut $var = ::core::pin::pin!($crate::__internal::StackInit$(::<$t>)?::uninit());
         let mut $var = $crate::__internal::StackInit::init($var, val)?;
+        $($crate::stack_try_pin_init!(let $($rest)*);)?
     };
 }
 
@@ -731,12 +943,17 @@ macro_rules! stack_try_pin_init {
 /// the following modifications is expected:
 /// - Fields that you want to initialize in-place have to use `<-` instead of `:`.
 /// - You can use `_: { /* run any user-code here */ },` anywhere where you can place fields in
-///   order to run arbitrary code.
+///   order to run arbitrary code. An `else` block after it is run when a later field fails, see
+///   [Rollback](#rollback).
 /// - In front of the initializer you can write `&this in` to have access to a [`NonNull<Self>`]
 ///   pointer named `this` inside of the initializer.
 /// - Using struct update syntax one can place `..Zeroable::init_zeroed()` at the very end of the
 ///   struct, this initializes every field with 0 and then runs all initializers specified in the
 ///   body. This can only be done if [`Zeroable`] is implemented for the struct.
+/// - A field can be initialized with a nested initializer `field: Inner { .. }` that uses the same
+///   syntax, see [Nested Initializers](#nested-initializers).
+/// - Fields can be initialized conditionally with `if` and `match` and array fields can be
+///   initialized with `for` loops, see [Control Flow](#control-flow).
 ///
 /// For instance:
 ///
@@ -765,6 +982,220 @@ macro_rules! stack_try_pin_init {
 /// });
 /// ```
 ///
+/// # Nested Initializers
+///
+/// When a field is itself a struct, it can be initialized in-place without another macro
+/// invocation. If the braces of a field value contain `<-`, `_:` or another nested initializer,
+/// the fields of the inner struct are initialized one by one, directly in the memory of the outer
+/// struct. The nested initializer shares the error type of the outer one and all initialized
+/// fields are dropped if a later field fails:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pin_init::*;
+/// #[pin_data]
+/// struct Counter {
+///     #[pin]
+///     value: CMutex<usize>,
+///     step: usize,
+/// }
+///
+/// #[pin_data]
+/// struct Stats {
+///     #[pin]
+///     hits: Counter,
+///     #[pin]
+///     misses: Counter,
+/// }
+///
+/// let init = pin_init!(Stats {
+///     hits: Counter {
+///         value <- CMutex::new(0),
+///         step: 1,
+///     },
+///     misses: Counter {
+///         value <- CMutex::new(0),
+///         step: hits.step,
+///     },
+/// });
+/// # let _ = Box::pin_init(init);
+/// ```
+///
+/// Just like with `pin_init!(Counter { .. })`, the inner struct needs to use `#[pin_data]` when
+/// used in `pin_init!`. Its fields are only structurally pinned if the field containing it is
+/// structurally pinned as well.
+///
+/// # Control Flow
+///
+/// To choose between different initializers, `if`/`else` and `match` can be used in place of
+/// fields. Their branches contain fields with the same syntax as the initializer itself and every
+/// branch has to initialize the same set of fields, possibly in a different order. Array fields can
+/// be initialized element by element with a `for` loop over the whole array:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pin_init::*;
+/// enum Mode {
+///     Fixed(usize),
+///     Counting,
+/// }
+///
+/// #[pin_data]
+/// struct Pool {
+///     shared: bool,
+///     #[pin]
+///     buckets: [CMutex<usize>; 4],
+/// }
+///
+/// fn new_pool(shared: bool, mode: Mode) -> impl PinInit<Pool> {
+///     pin_init!(Pool {
+///         if shared {
+///             shared: true,
+///         } else {
+///             shared: false,
+///         }
+///         match mode {
+///             Mode::Fixed(value) => {
+///                 for i in 0..4 {
+///                     buckets[i] <- CMutex::new(value)
+///                 }
+///             }
+///             Mode::Counting => {
+///                 for i in 0..4 {
+///                     buckets[i] <- CMutex::new(i)
+///                 }
+///             }
+///         }
+///     })
+/// }
+/// # let _ = Box::pin_init(new_pool(true, Mode::Counting));
+/// ```
+///
+/// An `if` needs an `else` branch. The range of a `for` loop has to be `0..N` where `N` is the
+/// length of the array, which is checked at compile time, or `..` to take the length from the
+/// type of the field. Since the body of the loop is called for every element, it cannot move
+/// values out of its environment.
+///
+/// # Rollback
+///
+/// When a field fails to initialize, the fields initialized before it are dropped. Side effects of
+/// code items that are not tied to a field can be undone with an `else` block, which is run only
+/// when a later field of the same struct fails:
+///
+/// ```rust
+/// # use pin_init::*;
+/// # use core::sync::atomic::{AtomicUsize, Ordering};
+/// static REGISTERED: AtomicUsize = AtomicUsize::new(0);
+///
+/// #[pin_data]
+/// struct Device {
+///     id: u32,
+///     irq: u32,
+/// }
+///
+/// fn new_device(irq: Result<u32, ()>) -> impl PinInit<Device, ()> {
+///     pin_init!(Device {
+///         id: 1,
+///         _: {
+///             REGISTERED.fetch_add(1, Ordering::Relaxed);
+///         } else {
+///             REGISTERED.fetch_sub(1, Ordering::Relaxed);
+///         },
+///         irq <- irq,
+///     }? ())
+/// }
+///
+/// stack_try_pin_init!(let device: Device = new_device(Err(())));
+/// assert!(device.is_err());
+/// assert_eq!(REGISTERED.load(Ordering::Relaxed), 0);
+/// stack_try_pin_init!(let device: Device = new_device(Ok(5)));
+/// assert!(device.is_ok());
+/// assert_eq!(REGISTERED.load(Ordering::Relaxed), 1);
+/// ```
+///
+/// The `else` blocks are run in reverse order, interleaved with the drops of the fields. They borrow
+/// the variables they use until the initializer finishes and are not supported inside of `if` and
+/// `match` items.
+///
+/// # Error Conversion
+///
+/// The error of every in-place field initializer (`<-`) is converted into the error type of the
+/// whole initializer with [`From`], in the same way as the `?` operator does it. This makes it
+/// possible to compose initializers from different subsystems without writing adapters:
+///
+/// ```rust
+/// # use pin_init::*;
+/// struct IrqError;
+/// struct QueueError;
+///
+/// enum DeviceError {
+///     Irq(IrqError),
+///     Queue(QueueError),
+/// }
+///
+/// impl From<IrqError> for DeviceError {
+///     fn from(e: IrqError) -> Self {
+///         Self::Irq(e)
+///     }
+/// }
+///
+/// impl From<QueueError> for DeviceError {
+///     fn from(e: QueueError) -> Self {
+///         Self::Queue(e)
+///     }
+/// }
+///
+/// # fn request_irq() -> impl Init<u32, IrqError> { Ok(5) }
+/// # fn new_queue() -> impl Init<[u8; 16], QueueError> { Ok([0; 16]) }
+/// #[pin_data]
+/// struct Device {
+///     irq: u32,
+///     queue: [u8; 16],
+/// }
+///
+/// fn new_device() -> impl PinInit<Device, DeviceError> {
+///     pin_init!(Device {
+///         irq <- request_irq(),
+///         queue <- new_queue(),
+///     }? DeviceError)
+/// }
+/// ```
+///
+/// Not every error type implements `From<Infallible>`, so nested initializers that cannot fail
+/// need to use the error type of the surrounding initializer. This is done by writing
+/// `inner <- init!(Inner { .. }? Error)` or by using the `#[default_error(Error)]` attribute on
+/// the nested initializer.
+///
+/// # Dynamically Sized Structs
+///
+/// Structs whose last field is a slice can be initialized by adding the `#[dst]` attribute
+/// to the initializer. The macro then checks that every field is mentioned using a struct
+/// pattern, since a struct expression requires the struct to be [`Sized`]. The initializer is
+/// given to [`InPlaceInitDst`] together with the length of the trailing slice:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # use pin_init::*;
+/// # use core::pin::Pin;
+/// #[pin_data]
+/// struct Packet {
+///     id: u32,
+///     data: [u8],
+/// }
+///
+/// # #[cfg(feature = "dst")]
+/// fn packet(id: u32, payload: &[u8]) -> Pin<Box<Packet>> {
+///     let len = payload.len();
+///     Box::pin_init_dst(len, pin_init!(#[dst] Packet {
+///         id,
+///         data <- init_slice_from_fn(len, |i| payload[i]),
+///     }))
+///     .unwrap()
+/// }
+/// ```
+///
 /// [`NonNull<Self>`]: core::ptr::NonNull
 pub use pin_init_internal::pin_init;
 
@@ -806,6 +1237,107 @@ pub use pin_init_internal::pin_init;
 /// ```
 pub use pin_init_internal::init;
 
+/// Chooses one of several initializers at runtime without boxing.
+///
+/// Takes a `match` expression or an `if`/`else` chain whose branches evaluate to initializers of
+/// the same type `T` with the same error `E`. Every branch is wrapped in nested [`EitherInit`]s, so
+/// the result implements [`PinInit<T, E>`] (or [`Init<T, E>`]) if all branches do.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use pin_init::*;
+///
+/// enum Config {
+///     Zero,
+///     Fixed(usize),
+///     Offset(usize),
+/// }
+///
+/// fn counter(config: Config) -> impl PinInit<CMutex<usize>> {
+///     select_init!(match config {
+///         Config::Zero => CMutex::new(0),
+///         Config::Fixed(value) => CMutex::new(value),
+///         Config::Offset(offset) => {
+///             let value = offset + 100;
+///             CMutex::new(value)
+///         }
+///     })
+/// }
+///
+/// fn flag(set: bool) -> impl Init<u8> {
+///     select_init!(if set {
+///         1
+///     } else {
+///         init_zeroed()
+///     })
+/// }
+/// # let _ = Box::pin_init(counter(Config::Offset(1)));
+/// # let _ = Box::init(flag(true));
+/// ```
+pub use pin_init_internal::select_init;
+
+/// Only expands its input when the `debug-poison` feature is enabled.
+///
+/// Used by the proc-macros, which cannot see the features of this crate.
+#[cfg(feature = "debug-poison")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __debug_poison {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+/// Only expands its input when the `debug-poison` feature is enabled.
+///
+/// Used by the proc-macros, which cannot see the features of this crate.
+#[cfg(not(feature = "debug-poison"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __debug_poison {
+    ($($tt:tt)*) => {};
+}
+
+/// Implements `FieldMeta` when the `debug-poison` feature is enabled.
+///
+/// `FieldMeta` is a specialization trait, so implementing it requires `min_specialization`. The
+/// implementation has to be written out here for `allow_internal_unstable` to apply to it.
+#[cfg(feature = "debug-poison")]
+#[doc(hidden)]
+#[macro_export]
+#[allow_internal_unstable(min_specialization)]
+macro_rules! __field_meta {
+    (
+        impl [$($impl_generics:tt)*] $source:ident for [$($ty:tt)*] [$($whr:tt)*]
+        fn ($slot:ident, $f:ident) { $($body:tt)* }
+    ) => {
+        // SAFETY: `__poisoned_fields` only reads the fields of `*slot`.
+        #[automatically_derived]
+        unsafe impl $($impl_generics)*
+            $crate::__internal::FieldMeta<$crate::__internal::$source> for $($ty)*
+            $($whr)*
+        {
+            unsafe fn __poisoned_fields(
+                $slot: *const Self,
+                $f: &mut dyn ::core::ops::FnMut(&'static str),
+            ) {
+                $($body)*
+            }
+        }
+    };
+}
+
+/// Implements `FieldMeta` when the `debug-poison` feature is enabled.
+#[cfg(not(feature = "debug-poison"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __field_meta {
+    ($($tt:tt)*) => {};
+}
+
 /// Asserts that a field on a struct using `#[pin_data]` is marked with `#[pin]` ie. that it is
 /// structurally pinned.
 ///
@@ -943,6 +1475,37 @@ pub unsafe trait PinInit<T: ?Sized, E = Infallible>: Sized {
     {
         ChainPinInit(self, f, __internal::PhantomInvariant::new())
     }
+
+    /// Turns a panic of this initializer into an error.
+    ///
+    /// The initializer is run inside of [`std::panic::catch_unwind`]. When it panics, the panic
+    /// payload is returned as [`InitPanic::Panic`] and the slot is left uninitialized, since
+    /// initializers drop everything they initialized when unwinding. Errors of the initializer are
+    /// returned as [`InitPanic::Error`].
+    ///
+    /// This is useful where panics must not propagate, e.g. at FFI boundaries. The initializer is
+    /// treated as [`UnwindSafe`](std::panic::UnwindSafe), so state it shares with the caller might
+    /// be observed in a broken state after a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![feature(allocator_api)]
+    /// # use pin_init::*;
+    /// use core::convert::Infallible;
+    ///
+    /// let init = init_array_from_fn::<_, 4, u8, Infallible>(|i| {
+    ///     assert!(i < 2, "index {i} is too large");
+    ///     i as u8
+    /// });
+    /// let res = Box::<[u8; 4]>::new_uninit().write_init(init.catch_unwind());
+    /// let err = res.unwrap_err();
+    /// assert_eq!(err.panic_message(), Some("index 2 is too large"));
+    /// ```
+    #[cfg(feature = "std")]
+    fn catch_unwind(self) -> CatchUnwind<Self, T, E> {
+        CatchUnwind(self, __internal::PhantomInvariant::new())
+    }
 }
 
 /// An initializer returned by [`PinInit::pin_chain`].
@@ -967,6 +1530,79 @@ where
     }
 }
 
+/// The error of an initializer returned by [`PinInit::catch_unwind`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum InitPanic<E> {
+    /// The initializer returned an error.
+    Error(E),
+    /// The initializer panicked with the contained payload.
+    Panic(std::boxed::Box<dyn core::any::Any + Send + 'static>),
+}
+
+#[cfg(feature = "std")]
+impl<E> InitPanic<E> {
+    /// Returns the message of the panic, if the initializer panicked with a string message.
+    pub fn panic_message(&self) -> Option<&str> {
+        match self {
+            Self::Error(_) => None,
+            Self::Panic(payload) => payload.downcast_ref::<&'static str>().copied().or_else(|| {
+                payload
+                    .downcast_ref::<std::string::String>()
+                    .map(|s| s.as_str())
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> From<E> for InitPanic<E> {
+    fn from(err: E) -> Self {
+        Self::Error(err)
+    }
+}
+
+/// An initializer returned by [`PinInit::catch_unwind`].
+#[cfg(feature = "std")]
+pub struct CatchUnwind<I, T: ?Sized, E>(I, __internal::PhantomInvariant<(E, T)>);
+
+// SAFETY: The `__pinned_init` function delegates to `self.0` and
+// - returns `Ok(())` when `self.0` successfully initialized `slot`,
+// - returns `Err(err)` when `self.0` returned an error or panicked. In both cases `self.0` left
+//   `slot` uninitialized.
+// - considers `slot` pinned, if `self.0` does.
+#[cfg(feature = "std")]
+unsafe impl<T: ?Sized, E, I> PinInit<T, InitPanic<E>> for CatchUnwind<I, T, E>
+where
+    I: PinInit<T, E>,
+{
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), InitPanic<E>> {
+        let init = self.0;
+        // SAFETY: All requirements fulfilled since this function is `__pinned_init`.
+        let f = std::panic::AssertUnwindSafe(|| unsafe { init.__pinned_init(slot) });
+        std::panic::catch_unwind(f).map_err(InitPanic::Panic)??;
+        Ok(())
+    }
+}
+
+// SAFETY: The `__init` function delegates to `self.0` and
+// - returns `Ok(())` when `self.0` successfully initialized `slot`,
+// - returns `Err(err)` when `self.0` returned an error or panicked. In both cases `self.0` left
+//   `slot` uninitialized.
+#[cfg(feature = "std")]
+unsafe impl<T: ?Sized, E, I> Init<T, InitPanic<E>> for CatchUnwind<I, T, E>
+where
+    I: Init<T, E>,
+{
+    unsafe fn __init(self, slot: *mut T) -> Result<(), InitPanic<E>> {
+        let init = self.0;
+        // SAFETY: All requirements fulfilled since this function is `__init`.
+        let f = std::panic::AssertUnwindSafe(|| unsafe { init.__init(slot) });
+        std::panic::catch_unwind(f).map_err(InitPanic::Panic)??;
+        Ok(())
+    }
+}
+
 /// An initializer for `T`.
 ///
 /// To use this initializer, you will need a suitable memory location that can hold a `T`. This can
@@ -1187,87 +1823,322 @@ pub fn uninit<T, E>() -> impl Init<MaybeUninit<T>, E> {
 /// Array initializer from element initializer.
 struct ArrayInit<T: ?Sized, F>(F, __internal::PhantomInvariant<T>);
 
-// SAFETY: On success, all `N` elements of the array have been initialized. On error or panic, the
-// elements that have been initialized so far are dropped, thus leaving the array uninitialized and
-// ready to deallocate.
+// SAFETY: `SliceInit` initializes all `N` elements, since it is created with length `N`.
 unsafe impl<T, F, I, E, const N: usize> PinInit<[T; N], E> for ArrayInit<T, F>
 where
     F: FnMut(usize) -> I,
     I: PinInit<T, E>,
 {
-    unsafe fn __pinned_init(mut self, slot: *mut [T; N]) -> Result<(), E> {
-        /// # Invariants
-        ///
-        /// - `ptr[..num_init]` contains initialized elements of type `T`
-        /// - `ptr[num_init..N]` (where N is the size of the array) contains uninitialized memory
-        struct ArrayInitGuard<T> {
-            /// A pointer to the first element of the array.
-            ptr: *mut T,
-            /// The number of initialized elements in the array.
-            num_init: usize,
-        }
+    #[inline]
+    unsafe fn __pinned_init(self, slot: *mut [T; N]) -> Result<(), E> {
+        let init = SliceInit::<T, F>(N, self.0, __internal::PhantomInvariant::new());
+        let slot: *mut [T] = slot;
+        // SAFETY: The safety requirements are the same, `slot` points to `N` elements.
+        unsafe { init.__pinned_init(slot) }
+    }
+}
 
-        impl<T> Drop for ArrayInitGuard<T> {
-            #[inline]
-            fn drop(&mut self) {
-                // SAFETY: Per type invariant, `self.ptr[..self.num_init]` are initialized.
-                unsafe {
-                    core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
-                        self.ptr,
-                        self.num_init,
-                    ))
-                };
-            }
-        }
+// SAFETY: Follows the `PinInit` impl. `__init` executes the same code as `__pinned_init`.
+unsafe impl<T, F, I, E, const N: usize> Init<[T; N], E> for ArrayInit<T, F>
+where
+    F: FnMut(usize) -> I,
+    I: Init<T, E>,
+{
+    #[inline(always)]
+    unsafe fn __init(self, slot: *mut [T; N]) -> Result<(), E> {
+        // SAFETY: `I: Init` cancels out the pinning requirement on subslots. The other safety
+        // requirements follow that of `__init`.
+        unsafe { self.__pinned_init(slot) }
+    }
+}
 
-        // INVARIANT: nothing is initialized yet.
-        let mut guard = ArrayInitGuard {
-            ptr: slot.cast::<T>(),
-            num_init: 0,
-        };
+/// Slice initializer from element initializer.
+///
+/// The first field is the expected length of the slice.
+struct SliceInit<T: ?Sized, F>(usize, F, __internal::PhantomInvariant<T>);
 
-        for i in 0..N {
-            // INVARIANT: Elements `self.ptr[..self.num_init]` have been initialized
-            // thus far. This holds true for every `self.num_init = i`.
-            guard.num_init = i;
-
-            let init = (self.0)(i);
-            // SAFETY:
-            // - The subslot is derived from `slot` with a valid offset.
-            // - If `Err` is touched, the subslot is not touched further, the guard will drop
-            //   previously initialized elements only.
-            // - `slot` is pinned so is the subslot.
-            unsafe { init.__pinned_init(&raw mut (*slot)[i]) }?;
+// SAFETY: On success, all elements of the slice have been initialized. On error or panic, the
+// `PinSliceBuilder` drops the elements that have been initialized so far, thus leaving the slice
+// uninitialized and ready to deallocate.
+unsafe impl<T, F, I, E> PinInit<[T], E> for SliceInit<T, F>
+where
+    F: FnMut(usize) -> I,
+    I: PinInit<T, E>,
+{
+    unsafe fn __pinned_init(mut self, slot: *mut [T]) -> Result<(), E> {
+        assert_eq!(
+            slot.len(),
+            self.0,
+            "the slice initializer has to be used on a slice of the same length"
+        );
+        // SAFETY: `slot` is valid for writes, uninitialized and pinned.
+        let mut builder = unsafe { PinSliceBuilder::new(slot) };
+        while !builder.is_full() {
+            let init = (self.1)(builder.len());
+            builder.push_pin_init(init).map_err(|(_, err)| err)?;
         }
-
-        // Dismiss the drop guard now that all elements are initialized.
-        core::mem::forget(guard);
+        builder.finish();
         Ok(())
     }
 }
 
 // SAFETY: Follows the `PinInit` impl. `__init` executes the same code as `__pinned_init`.
-unsafe impl<T, F, I, E, const N: usize> Init<[T; N], E> for ArrayInit<T, F>
+unsafe impl<T, F, I, E> Init<[T], E> for SliceInit<T, F>
 where
     F: FnMut(usize) -> I,
     I: Init<T, E>,
 {
     #[inline(always)]
-    unsafe fn __init(self, slot: *mut [T; N]) -> Result<(), E> {
+    unsafe fn __init(self, slot: *mut [T]) -> Result<(), E> {
         // SAFETY: `I: Init` cancels out the pinning requirement on subslots. The other safety
         // requirements follow that of `__init`.
         unsafe { self.__pinned_init(slot) }
     }
 }
 
-/// Initializes an array by initializing each element via the provided initializer.
+/// Initializes the elements of a slice or an array one by one.
+///
+/// This is a building block for initializers of slices and arrays: it keeps track of the elements
+/// that have been initialized so far and drops them when it is dropped itself, which happens when
+/// one of the element initializers fails or panics. Failures are reported together with the index
+/// of the element. Arrays can be initialized by coercing the `*mut [T; N]` slot to a `*mut [T]`.
+///
+/// Elements can only be initialized with [`Init`], use [`PinSliceBuilder`] for [`PinInit`].
 ///
 /// # Examples
 ///
 /// ```rust
-/// # use pin_init::*;
-/// use pin_init::init_array_from_fn;
-/// let array: Box<[usize; 1_000]> = Box::init(init_array_from_fn(|i| i)).unwrap();
+/// use core::num::ParseIntError;
+/// use pin_init::*;
+///
+/// fn parse_all<const N: usize>(
+///     input: [&str; N],
+/// ) -> impl Init<[u32; N], (usize, ParseIntError)> + '_ {
+///     // SAFETY: On success, all elements have been initialized. On error, the builder drops the
+///     // elements that have been initialized so far.
+///     unsafe {
+///         init_from_closure(move |slot: *mut [u32; N]| {
+///             let mut builder = SliceBuilder::new(slot);
+///             for s in input {
+///                 builder.push_init(s.parse())?;
+///             }
+///             builder.finish();
+///             Ok(())
+///         })
+///     }
+/// }
+///
+/// stack_try_pin_init!(let values = parse_all(["1", "2", "3"]));
+/// assert_eq!(*values.unwrap(), [1, 2, 3]);
+/// stack_try_pin_init!(let values = parse_all(["1", "x", "3"]));
+/// assert_eq!(values.err().map(|(index, _)| index), Some(1));
+/// ```
+///
+/// # Invariants
+///
+/// - `ptr[..num_init]` contains initialized elements of type `T`
+/// - `ptr[num_init..capacity]` contains uninitialized memory
+pub struct SliceBuilder<'a, T> {
+    /// A pointer to the first element of the slice.
+    ptr: *mut T,
+    /// The length of the slice.
+    capacity: usize,
+    /// The number of initialized elements in the slice.
+    num_init: usize,
+    /// The slice is borrowed for `'a` and handed out by [`SliceBuilder::finish`].
+    _p: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> SliceBuilder<'a, T> {
+    /// Creates a new builder for the given slot.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` is valid for reads and writes and uninitialized,
+    /// - `slot` is not accessed other than through the builder and the slice returned by
+    ///   [`SliceBuilder::finish`] for the duration of `'a`.
+    #[inline]
+    pub unsafe fn new(slot: *mut [T]) -> Self {
+        Self {
+            ptr: slot.cast::<T>(),
+            capacity: slot.len(),
+            num_init: 0,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements that have been initialized.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.num_init
+    }
+
+    /// Returns `true` if no element has been initialized.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.num_init == 0
+    }
+
+    /// Returns the length of the slice.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if all elements have been initialized.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.num_init == self.capacity
+    }
+
+    /// Initializes the next element using the given initializer.
+    ///
+    /// On failure, the index of the element is returned together with the error. The elements
+    /// initialized before are kept and dropped together with the builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all elements have been initialized.
+    #[inline]
+    pub fn push_init<E>(&mut self, init: impl Init<T, E>) -> Result<(), (usize, E)> {
+        // SAFETY: `I: Init` cancels out the pinning requirement.
+        unsafe { self.push_with(|slot| init.__init(slot)) }
+    }
+
+    /// # Safety
+    ///
+    /// `init` has the same safety requirements and guarantees as `PinInit::__pinned_init`.
+    unsafe fn push_with<E>(
+        &mut self,
+        init: impl FnOnce(*mut T) -> Result<(), E>,
+    ) -> Result<(), (usize, E)> {
+        assert!(
+            !self.is_full(),
+            "all elements have already been initialized"
+        );
+        let index = self.num_init;
+        // SAFETY: `index < self.capacity`, so the element is inside of the slice.
+        let slot = unsafe { self.ptr.add(index) };
+        init(slot).map_err(|err| (index, err))?;
+        // INVARIANT: The element at `index` has been initialized.
+        self.num_init += 1;
+        Ok(())
+    }
+
+    /// Finishes the initialization, leaving the elements initialized, and returns the initialized
+    /// slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not all elements have been initialized.
+    #[inline]
+    pub fn finish(self) -> &'a mut [T] {
+        assert!(self.is_full(), "not all elements have been initialized");
+        let this = ManuallyDrop::new(self);
+        // SAFETY: All `capacity` elements have been initialized and the slice is borrowed for `'a`
+        // by the requirements of `SliceBuilder::new`.
+        unsafe { core::slice::from_raw_parts_mut(this.ptr, this.capacity) }
+    }
+}
+
+impl<T> Drop for SliceBuilder<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: `self.ptr[..self.num_init]` are initialized.
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.ptr, self.num_init))
+        };
+    }
+}
+
+/// Pin-initializes the elements of a pinned slice or array one by one.
+///
+/// The pinned counterpart of [`SliceBuilder`]: elements can also be initialized with [`PinInit`],
+/// so the initialized slice is only handed out pinned.
+pub struct PinSliceBuilder<'a, T>(SliceBuilder<'a, T>);
+
+impl<'a, T> PinSliceBuilder<'a, T> {
+    /// Creates a new builder for the given slot.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` is valid for reads and writes and uninitialized,
+    /// - `slot` is not accessed other than through the builder and the slice returned by
+    ///   [`PinSliceBuilder::finish`] for the duration of `'a`,
+    /// - `slot` is pinned.
+    #[inline]
+    pub unsafe fn new(slot: *mut [T]) -> Self {
+        // SAFETY: The requirements of `SliceBuilder::new` are a subset of ours.
+        Self(unsafe { SliceBuilder::new(slot) })
+    }
+
+    /// Returns the number of elements that have been initialized.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no element has been initialized.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the length of the slice.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Returns `true` if all elements have been initialized.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Initializes the next element using the given initializer.
+    ///
+    /// See [`SliceBuilder::push_init`].
+    #[inline]
+    pub fn push_init<E>(&mut self, init: impl Init<T, E>) -> Result<(), (usize, E)> {
+        self.0.push_init(init)
+    }
+
+    /// Pin-initializes the next element using the given initializer.
+    ///
+    /// On failure, the index of the element is returned together with the error. The elements
+    /// initialized before are kept and dropped together with the builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all elements have been initialized.
+    #[inline]
+    pub fn push_pin_init<E>(&mut self, init: impl PinInit<T, E>) -> Result<(), (usize, E)> {
+        // SAFETY: The slot is pinned by the requirements of `PinSliceBuilder::new`.
+        unsafe { self.0.push_with(|slot| init.__pinned_init(slot)) }
+    }
+
+    /// Finishes the initialization, leaving the elements initialized, and returns the initialized
+    /// slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not all elements have been initialized.
+    #[inline]
+    pub fn finish(self) -> Pin<&'a mut [T]> {
+        // SAFETY: The slice is pinned by the requirements of `PinSliceBuilder::new`.
+        unsafe { Pin::new_unchecked(self.0.finish()) }
+    }
+}
+
+/// Initializes an array by initializing each element via the provided initializer.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pin_init::*;
+/// use pin_init::init_array_from_fn;
+/// let array: Box<[usize; 1_000]> = Box::init(init_array_from_fn(|i| i)).unwrap();
 /// assert_eq!(array.len(), 1_000);
 /// ```
 pub fn init_array_from_fn<I, const N: usize, T, E>(
@@ -1303,6 +2174,221 @@ where
     ArrayInit(make_init, __internal::PhantomInvariant::new())
 }
 
+/// Initializes a slice of length `len` by initializing each element via the provided initializer.
+///
+/// This is mostly useful for the trailing slice field of a dynamically sized struct, see
+/// [`InPlaceInitDst`].
+///
+/// # Panics
+///
+/// Panics when the initializer is used on a slice whose length is not `len`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use pin_init::*;
+/// # #[cfg(feature = "dst")] {
+/// let slice: Box<[usize]> = Box::init_dst(4, init_slice_from_fn(4, |i| i * 2)).unwrap();
+/// assert_eq!(*slice, [0, 2, 4, 6]);
+/// # }
+/// ```
+pub fn init_slice_from_fn<I, T, E>(
+    len: usize,
+    make_init: impl FnMut(usize) -> I,
+) -> impl Init<[T], E>
+where
+    I: Init<T, E>,
+{
+    SliceInit(len, make_init, __internal::PhantomInvariant::new())
+}
+
+/// Initializes a slice of length `len` by initializing each element via the provided initializer.
+///
+/// This is mostly useful for the trailing slice field of a dynamically sized struct, see
+/// [`InPlaceInitDst`].
+///
+/// # Panics
+///
+/// Panics when the initializer is used on a slice whose length is not `len`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pin_init::*;
+/// # use core::pin::Pin;
+/// # #[cfg(feature = "dst")] {
+/// let slice: Pin<Box<[CMutex<usize>]>> =
+///     Box::pin_init_dst(100, pin_init_slice_from_fn(100, |i| CMutex::new(i))).unwrap();
+/// assert_eq!(slice.len(), 100);
+/// # }
+/// ```
+pub fn pin_init_slice_from_fn<I, T, E>(
+    len: usize,
+    make_init: impl FnMut(usize) -> I,
+) -> impl PinInit<[T], E>
+where
+    I: PinInit<T, E>,
+{
+    SliceInit(len, make_init, __internal::PhantomInvariant::new())
+}
+
+/// Returns a pinned mutable reference to the element at `index` of a pinned slice, or `None` if
+/// `index` is out of bounds.
+///
+/// The elements of a pinned slice or array are structurally pinned. A pinned array, such as the
+/// ones created by [`stack_pin_init!`], coerces to a pinned slice.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use pin_init::*;
+///
+/// stack_pin_init!(let mutexes: [CMutex<usize>; 4] = |i| CMutex::new(i));
+/// let second = pin_slice_get_mut(mutexes.as_mut(), 1).unwrap();
+/// *second.lock() += 10;
+/// assert_eq!(*mutexes[1].lock(), 11);
+/// ```
+pub fn pin_slice_get_mut<T>(slice: Pin<&mut [T]>, index: usize) -> Option<Pin<&mut T>> {
+    // SAFETY: The element is not moved out of the slice and is structurally pinned.
+    unsafe { slice.get_unchecked_mut().get_mut(index) }
+        // SAFETY: The element is pinned, since the slice is pinned.
+        .map(|elem| unsafe { Pin::new_unchecked(elem) })
+}
+
+/// Returns an iterator over pinned mutable references to the elements of a pinned slice.
+///
+/// See [`pin_slice_get_mut`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use pin_init::*;
+///
+/// stack_pin_init!(let mutexes: [CMutex<usize>; 4] = |i| CMutex::new(i));
+/// for mutex in pin_slice_iter_mut(mutexes.as_mut()) {
+///     *mutex.lock() *= 2;
+/// }
+/// assert_eq!(*mutexes[3].lock(), 6);
+/// ```
+pub fn pin_slice_iter_mut<T>(slice: Pin<&mut [T]>) -> impl Iterator<Item = Pin<&mut T>> {
+    // SAFETY: The elements are not moved out of the slice and are structurally pinned.
+    unsafe { slice.get_unchecked_mut() }
+        .iter_mut()
+        // SAFETY: The elements are pinned, since the slice is pinned.
+        .map(|elem| unsafe { Pin::new_unchecked(elem) })
+}
+
+/// Writes `value` to the `len` elements starting at `ptr`.
+///
+/// Instead of writing every element individually, the initialized prefix is doubled in size with
+/// every step, so this needs `O(log len)` calls to `copy_nonoverlapping`.
+///
+/// # Safety
+///
+/// `ptr` is valid for writes of `len` elements.
+unsafe fn fill<T: Copy>(ptr: *mut T, len: usize, value: T) {
+    if len == 0 {
+        return;
+    }
+    // SAFETY: `len > 0`, so the first element is valid for writes.
+    unsafe { ptr.write(value) };
+    let mut filled = 1;
+    while filled < len {
+        let count = filled.min(len - filled);
+        // SAFETY: `ptr[..filled]` is initialized and `ptr[filled..filled + count]` is valid for
+        // writes. The two do not overlap.
+        unsafe { ptr::copy_nonoverlapping(ptr, ptr.add(filled), count) };
+        filled += count;
+    }
+}
+
+/// Initializes every element of an array with a copy of `value`.
+///
+/// The elements are copied in bulk instead of calling a closure for every element as
+/// [`init_array_from_fn`] does. Neither copies the array on the stack, so both can be used for
+/// large arrays; `benches/fill.rs` compares their performance.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pin_init::*;
+/// let array: Box<[u32; 1_000]> = Box::init(init_array_repeat(7)).unwrap();
+/// assert!(array.iter().all(|x| *x == 7));
+/// ```
+pub fn init_array_repeat<T: Copy, const N: usize, E>(value: T) -> impl Init<[T; N], E> {
+    // SAFETY: All elements of the array are initialized by `fill`, since `slot` is valid for writes
+    // of `N` elements. The initializer cannot fail.
+    unsafe {
+        init_from_closure(move |slot: *mut [T; N]| {
+            fill(slot.cast::<T>(), N, value);
+            Ok(())
+        })
+    }
+}
+
+/// Initializes every element of a slice of length `len` with a copy of `value`.
+///
+/// # Panics
+///
+/// Panics when the initializer is used on a slice whose length is not `len`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # use pin_init::*;
+/// # #[cfg(feature = "dst")] {
+/// let slice: Box<[u8]> = Box::init_dst(16, init_slice_fill(0xff, 16)).unwrap();
+/// assert_eq!(*slice, [0xff; 16]);
+/// # }
+/// ```
+pub fn init_slice_fill<T: Copy, E>(value: T, len: usize) -> impl Init<[T], E> {
+    // SAFETY: All elements of the slice are initialized by `fill`, since `slot` is valid for
+    // writes of `len` elements. The initializer cannot fail.
+    unsafe {
+        init_from_closure(move |slot: *mut [T]| {
+            assert_eq!(
+                slot.len(),
+                len,
+                "the slice initializer has to be used on a slice of the same length"
+            );
+            fill(slot.cast::<T>(), len, value);
+            Ok(())
+        })
+    }
+}
+
+/// Initializes an array by copying the elements of `src`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pin_init::*;
+/// static TABLE: [u16; 4] = [1, 2, 4, 8];
+/// let array: Box<[u16; 4]> = Box::init(init_array_copy_from(&TABLE)).unwrap();
+/// assert_eq!(*array, TABLE);
+/// ```
+pub fn init_array_copy_from<T: Copy, const N: usize, E>(
+    src: &[T; N],
+) -> impl Init<[T; N], E> + use<'_, T, N, E> {
+    // SAFETY: All elements of the array are initialized by copying them from `src`. `slot` is valid
+    // for writes of `N` elements and does not overlap with `src`, since it is uninitialized. The
+    // initializer cannot fail.
+    unsafe {
+        init_from_closure(move |slot: *mut [T; N]| {
+            ptr::copy_nonoverlapping(src.as_ptr(), slot.cast::<T>(), N);
+            Ok(())
+        })
+    }
+}
+
 /// Construct an initializer in a closure and run it.
 ///
 /// Returns an initializer that first runs the closure and then the initializer returned by it.
@@ -1390,6 +2476,298 @@ where
     }
 }
 
+/// An initializer that is one of two initializers.
+///
+/// Different initializers have different types, so a function returning `impl PinInit<T, E>`
+/// cannot return one of several initializers depending on a runtime value. `EitherInit` combines
+/// two initializers of the same type `T` with the same error `E` into one type that implements
+/// [`PinInit<T, E>`] (or [`Init<T, E>`]) if both of them do. No heap allocation is needed.
+///
+/// `T` and `E` are part of the type only to distinguish it from other initializers and are
+/// usually inferred.
+///
+/// For more than two initializers, use [`select_init!`], which nests `EitherInit`s.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use pin_init::*;
+///
+/// fn counter(start: Option<usize>) -> impl PinInit<CMutex<usize>> {
+///     match start {
+///         Some(start) => EitherInit::Left(CMutex::new(start)),
+///         None => EitherInit::Right(CMutex::new(usize::MAX)),
+///     }
+/// }
+/// # let _ = Box::pin_init(counter(None));
+/// ```
+pub enum EitherInit<A, B, T: ?Sized, E = Infallible> {
+    /// The first initializer.
+    Left(A),
+    /// The second initializer.
+    Right(B),
+    // The type parameters `T` and `E` prevent overlap with the `PinInit<T> for T` impl.
+    #[doc(hidden)]
+    __Phantom(Infallible, __internal::PhantomInvariant<(E, T)>),
+}
+
+// SAFETY: Delegates to the contained initializer, which upholds the safety requirements.
+unsafe impl<T: ?Sized, E, A, B> PinInit<T, E> for EitherInit<A, B, T, E>
+where
+    A: PinInit<T, E>,
+    B: PinInit<T, E>,
+{
+    #[inline]
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        match self {
+            // SAFETY: Per the safety requirements of this function.
+            Self::Left(init) => unsafe { init.__pinned_init(slot) },
+            // SAFETY: Per the safety requirements of this function.
+            Self::Right(init) => unsafe { init.__pinned_init(slot) },
+            Self::__Phantom(never, _) => match never {},
+        }
+    }
+}
+
+// SAFETY: Delegates to the contained initializer, which upholds the safety requirements.
+unsafe impl<T: ?Sized, E, A, B> Init<T, E> for EitherInit<A, B, T, E>
+where
+    A: Init<T, E>,
+    B: Init<T, E>,
+{
+    #[inline]
+    unsafe fn __init(self, slot: *mut T) -> Result<(), E> {
+        match self {
+            // SAFETY: Per the safety requirements of this function.
+            Self::Left(init) => unsafe { init.__init(slot) },
+            // SAFETY: Per the safety requirements of this function.
+            Self::Right(init) => unsafe { init.__init(slot) },
+            Self::__Phantom(never, _) => match never {},
+        }
+    }
+}
+
+/// Error of an initializer that records which field failed to initialize.
+///
+/// This error type is produced by [`pin_init!`] and [`init!`] when the `#[field_errors]` attribute
+/// is placed in front of the initializer. The error returned by a failing field initializer is
+/// converted into `E` with [`From`] and stored in [`InitError::source`], the path to the field is
+/// recorded alongside it. When the failing field initializer itself returns an [`InitError`] (for
+/// example because it is another `#[field_errors]` initializer), the paths are joined. Thus a
+/// failure of `irq` inside of the `queue` field of `Device` is reported as `Device.queue.irq`. The
+/// elements of arrays initialized by `for` items are recorded with their index, as in
+/// `Device.queues[3].irq`.
+///
+/// The path is stored inline and holds at most [`InitError::MAX_DEPTH`] segments, the outermost
+/// fields are omitted when the nesting is deeper than that.
+///
+/// # Examples
+///
+/// ```rust
+/// use pin_init::*;
+///
+/// #[derive(Debug)]
+/// struct IrqError;
+///
+/// #[pin_data]
+/// struct Queue {
+///     len: usize,
+///     irq: u32,
+/// }
+///
+/// #[pin_data]
+/// struct Device {
+///     id: u64,
+///     #[pin]
+///     queue: Queue,
+/// }
+///
+/// fn request_irq() -> impl Init<u32, IrqError> {
+///     Err(IrqError)
+/// }
+///
+/// let queue = pin_init!(#[field_errors] Queue {
+///     len: 16,
+///     irq <- request_irq(),
+/// }? IrqError);
+/// let device = pin_init!(#[field_errors] Device {
+///     id: 0,
+///     queue <- queue,
+/// }? IrqError);
+///
+/// stack_try_pin_init!(let device: Device = device);
+/// let err = device.err().unwrap();
+/// assert_eq!(err.field(), Some("irq"));
+/// assert_eq!(err.to_string(), "failed to initialize `Device.queue.irq`");
+/// ```
+pub struct InitError<E> {
+    /// The error returned by the failing initializer.
+    pub source: E,
+    // INVARIANT: `segments[..len]` contains the path in reverse order, i.e. the innermost field
+    // first and the name of the outermost type last.
+    segments: [PathSegment; INIT_ERROR_MAX_DEPTH],
+    len: u8,
+    truncated: bool,
+}
+
+const INIT_ERROR_MAX_DEPTH: usize = 6;
+
+impl<E> InitError<E> {
+    /// The maximum number of segments (type name and field names) stored in the path.
+    pub const MAX_DEPTH: usize = INIT_ERROR_MAX_DEPTH;
+
+    /// Returns the name of the innermost field that failed to initialize.
+    ///
+    /// Returns `None` if the error was not returned by a field initializer, for example when it
+    /// was returned from a `_: { ... }` code block.
+    pub fn field(&self) -> Option<&'static str> {
+        // The last segment is the name of the outermost type.
+        let fields = usize::from(self.len).saturating_sub(1);
+        self.segments[..fields]
+            .iter()
+            .find_map(|segment| match segment {
+                PathSegment::Name(name) => Some(*name),
+                PathSegment::Index(_) => None,
+            })
+    }
+
+    /// Returns the segments of the path to the failing field.
+    ///
+    /// The first segment is the name of the outermost type, the remaining segments are the field
+    /// names and array indices leading to the failing field.
+    pub fn path(&self) -> impl DoubleEndedIterator<Item = PathSegment> + '_ {
+        self.segments[..usize::from(self.len)].iter().rev().copied()
+    }
+
+    /// Records that this error was produced while initializing `field` of the type named `ty`, or
+    /// the element at `index` of it if `field` is an array initialized by a `for` item.
+    pub(crate) fn in_field(
+        mut self,
+        ty: &'static str,
+        field: &'static str,
+        index: Option<usize>,
+    ) -> Self {
+        // Replace the name of the nested type with the name of the field.
+        if self.len > 0 {
+            self.len -= 1;
+        }
+        if let Some(index) = index {
+            self.push(PathSegment::Index(index));
+        }
+        self.push(PathSegment::Name(field));
+        self.segments[usize::from(self.len)] = PathSegment::Name(ty);
+        self.len += 1;
+        self
+    }
+
+    /// Appends `segment`, leaving room for the name of the type.
+    fn push(&mut self, segment: PathSegment) {
+        if usize::from(self.len) < Self::MAX_DEPTH - 1 {
+            self.segments[usize::from(self.len)] = segment;
+            self.len += 1;
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    /// Converts the source of this error, keeping the path.
+    pub(crate) fn map_source<F>(self, f: impl FnOnce(E) -> F) -> InitError<F> {
+        InitError {
+            source: f(self.source),
+            segments: self.segments,
+            len: self.len,
+            truncated: self.truncated,
+        }
+    }
+}
+
+/// A segment of the path recorded by [`InitError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment {
+    /// The name of a type or of a field.
+    Name(&'static str),
+    /// The index of an element of an array field.
+    Index(usize),
+}
+
+impl PartialEq<&str> for PathSegment {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Self::Name(name) if name == other)
+    }
+}
+
+impl core::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+impl<E> From<E> for InitError<E> {
+    fn from(source: E) -> Self {
+        Self {
+            source,
+            segments: [PathSegment::Name(""); INIT_ERROR_MAX_DEPTH],
+            len: 0,
+            truncated: false,
+        }
+    }
+}
+
+impl<E> InitError<E> {
+    fn fmt_path(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut path = self.path();
+        if let Some(ty) = path.next() {
+            write!(f, "{ty}")?;
+        }
+        if self.truncated {
+            f.write_str(".[..]")?;
+        }
+        for segment in path {
+            match segment {
+                PathSegment::Name(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Debug for InitError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct Path<'a, E>(&'a InitError<E>);
+
+        impl<E> core::fmt::Debug for Path<'_, E> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.0.fmt_path(f)
+            }
+        }
+
+        f.debug_struct("InitError")
+            .field("path", &Path(self))
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl<E> core::fmt::Display for InitError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("failed to initialize `")?;
+        self.fmt_path(f)?;
+        f.write_str("`")
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for InitError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 // SAFETY: the `__init` function always returns `Ok(())` and initializes every field of `slot`.
 unsafe impl<T> Init<T> for T {
     unsafe fn __init(self, slot: *mut T) -> Result<(), Infallible> {
@@ -1454,7 +2832,7 @@ impl<T> InPlaceWrite<T> for &'static mut MaybeUninit<T> {
         let slot = self.as_mut_ptr();
 
         // SAFETY: `slot` is a valid pointer to uninitialized memory.
-        unsafe { init.__init(slot)? };
+        unsafe { poison::checked_init(init).__init(slot)? };
 
         // SAFETY: The above call initialized the memory.
         unsafe { Ok(self.assume_init_mut()) }
@@ -1467,13 +2845,342 @@ impl<T> InPlaceWrite<T> for &'static mut MaybeUninit<T> {
         //
         // The `'static` borrow guarantees the data will not be
         // moved/invalidated until it gets dropped (which is never).
-        unsafe { init.__pinned_init(slot)? };
+        unsafe { poison::checked(init).__pinned_init(slot)? };
 
         // SAFETY: The above call initialized the memory.
         Ok(Pin::static_mut(unsafe { self.assume_init_mut() }))
     }
 }
 
+/// A pinned memory location that can hold a value of type `T`.
+///
+/// The slot starts out empty and can be initialized, dropped and re-initialized any number of
+/// times without moving or reallocating, so pinned objects that only live for a limited time (e.g.
+/// for the duration of a request) can reuse the same memory. Since it can be put into any pinned
+/// location (on the stack, as a `#[pin]` field or in a `static`), no allocation is needed.
+///
+/// A value in the slot is dropped when it is replaced, when [`take_drop`] is called and when the
+/// slot itself is dropped.
+///
+/// # Invariants
+///
+/// If `self.is_init` is true, then `self.value` is initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::pin::pin;
+/// use pin_init::*;
+///
+/// let mut slot = pin!(PinSlot::<CMutex<usize>>::new());
+/// for request in 0..3 {
+///     let mutex = slot.as_mut().pin_init(CMutex::new(request));
+///     *mutex.lock() += 1;
+///     assert_eq!(*slot.as_ref().value().unwrap().lock(), request + 1);
+/// }
+/// slot.as_mut().take_drop();
+/// assert!(!slot.is_init());
+/// ```
+///
+/// [`take_drop`]: PinSlot::take_drop
+pub struct PinSlot<T> {
+    value: MaybeUninit<T>,
+    is_init: bool,
+}
+
+impl<T> PinSlot<T> {
+    /// Creates a new empty slot.
+    #[inline]
+    pub const fn new() -> Self {
+        // INVARIANT: `is_init` is false.
+        Self {
+            value: MaybeUninit::uninit(),
+            is_init: false,
+        }
+    }
+
+    /// Returns `true` if the slot contains a value.
+    #[inline]
+    pub fn is_init(&self) -> bool {
+        self.is_init
+    }
+
+    /// Drops the current value, if any, and pin-initializes a new one in its place.
+    ///
+    /// When `init` fails, the slot is left empty.
+    pub fn try_pin_init<E>(
+        mut self: Pin<&mut Self>,
+        init: impl PinInit<T, E>,
+    ) -> Result<Pin<&mut T>, E> {
+        self.as_mut().take_drop();
+        // SAFETY: We never move out of `this`.
+        let this = unsafe { Pin::into_inner_unchecked(self) };
+        // SAFETY: The slot is empty, so `this.value` is uninitialized. It is pinned, since `self`
+        // is pinned.
+        unsafe { poison::checked(init).__pinned_init(this.value.as_mut_ptr())? };
+        // INVARIANT: `this.value` is initialized above.
+        this.is_init = true;
+        // SAFETY: The value is pinned, since `self` is pinned.
+        Ok(unsafe { Pin::new_unchecked(this.value.assume_init_mut()) })
+    }
+
+    /// Drops the current value, if any, and pin-initializes a new one in its place.
+    pub fn pin_init(self: Pin<&mut Self>, init: impl PinInit<T>) -> Pin<&mut T> {
+        match self.try_pin_init(init) {
+            Ok(value) => value,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Drops the current value, if any, leaving the slot empty.
+    pub fn take_drop(self: Pin<&mut Self>) {
+        // SAFETY: We never move out of `this`.
+        let this = unsafe { Pin::into_inner_unchecked(self) };
+        if this.is_init {
+            // INVARIANT: The slot is marked as empty before the value is dropped, so it stays
+            // empty when dropping panics.
+            this.is_init = false;
+            // SAFETY: `this.is_init` was true and therefore `this.value` is initialized. Pinned
+            // values are allowed to be dropped in place.
+            unsafe { this.value.assume_init_drop() };
+        }
+    }
+
+    /// Returns a pinned reference to the value, if the slot contains one.
+    pub fn value(self: Pin<&Self>) -> Option<Pin<&T>> {
+        let this = self.get_ref();
+        // SAFETY: `this.is_init` is true and therefore `this.value` is initialized. The value is
+        // pinned, since `self` is pinned.
+        this.is_init
+            .then(|| unsafe { Pin::new_unchecked(this.value.assume_init_ref()) })
+    }
+
+    /// Returns a pinned mutable reference to the value, if the slot contains one.
+    pub fn value_mut(self: Pin<&mut Self>) -> Option<Pin<&mut T>> {
+        // SAFETY: We never move out of `this`.
+        let this = unsafe { Pin::into_inner_unchecked(self) };
+        // SAFETY: `this.is_init` is true and therefore `this.value` is initialized. The value is
+        // pinned, since `self` is pinned.
+        this.is_init
+            .then(|| unsafe { Pin::new_unchecked(this.value.assume_init_mut()) })
+    }
+}
+
+impl<T> Default for PinSlot<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for PinSlot<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.is_init {
+            // SAFETY: `self.is_init` is true and therefore `self.value` is initialized. It is not
+            // used afterwards, since the slot is being dropped.
+            unsafe { self.value.assume_init_drop() };
+        }
+    }
+}
+
+/// Pin-initializes a value in borrowed memory and runs `f` with it.
+///
+/// [`InPlaceWrite`] is only implemented for `&'static mut MaybeUninit<T>`, since a pinned value in
+/// memory with a shorter lifetime has to be dropped before the memory is reused, which cannot be
+/// guaranteed when the pinned reference is returned. This function instead drops the value after
+/// `f` returns or panics, so any memory can host the value for the duration of `f`.
+///
+/// When `init` fails, `f` is not called and the error is returned.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use core::mem::MaybeUninit;
+/// use pin_init::*;
+///
+/// let mut arena = [const { MaybeUninit::<CMutex<usize>>::uninit() }; 4];
+/// let sum = scope_pin(&mut arena[1], CMutex::new(20), |mutex| {
+///     *mutex.lock() += 1;
+///     *mutex.lock() * 2
+/// });
+/// assert_eq!(sum, Ok::<_, core::convert::Infallible>(42));
+/// ```
+pub fn scope_pin<T, E, R>(
+    slot: &mut MaybeUninit<T>,
+    init: impl PinInit<T, E>,
+    f: impl FnOnce(Pin<&mut T>) -> R,
+) -> Result<R, E> {
+    let slot = slot.as_mut_ptr();
+    // SAFETY: `slot` is a valid pointer to uninitialized memory. It stays valid until this function
+    // returns and the value is dropped by the guard below before that, even when `f` panics.
+    unsafe { init.__pinned_init(slot)? };
+    // SAFETY: `slot` has been initialized above and is pinned until the guard drops the value.
+    let mut guard = unsafe { __internal::DropGuard::<__internal::Pinned, T>::new(slot) };
+    Ok(f(guard.let_binding()))
+}
+
+/// A value that has been initialized in borrowed memory.
+///
+/// The value is dropped when the guard is dropped. Forgetting the guard leaks the value, which is
+/// sound, since the value is not pinned. For pinned values use [`scope_pin`].
+///
+/// # Examples
+///
+/// ```rust
+/// use core::mem::MaybeUninit;
+/// use pin_init::*;
+///
+/// struct Buffer {
+///     len: usize,
+///     data: [u8; 1024],
+/// }
+///
+/// let mut slot = MaybeUninit::uninit();
+/// let mut buf = InitGuard::new(&mut slot, init!(Buffer { len: 0, data <- init_zeroed() }))
+///     .unwrap_or_else(|e| match e {});
+/// buf.data[0] = 42;
+/// buf.len = 1;
+/// assert_eq!(buf.data[..buf.len], [42]);
+/// ```
+pub struct InitGuard<'a, T> {
+    value: &'a mut T,
+}
+
+impl<'a, T> InitGuard<'a, T> {
+    /// Initializes `slot` with `init` and returns a guard owning the value.
+    ///
+    /// Does not drop the current value of `slot` and considers it as uninitialized memory.
+    pub fn new<E>(slot: &'a mut MaybeUninit<T>, init: impl Init<T, E>) -> Result<Self, E> {
+        // SAFETY: `slot` is a valid pointer to uninitialized memory.
+        unsafe { init.__init(slot.as_mut_ptr())? };
+        Ok(Self {
+            // SAFETY: The above call initialized the memory.
+            value: unsafe { slot.assume_init_mut() },
+        })
+    }
+
+    /// Moves the value out of the slot.
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: The value is initialized and owned by the guard, which is not dropped, so the
+        // value is not used afterwards.
+        unsafe { ptr::read(&*this.value) }
+    }
+}
+
+impl<T> Deref for InitGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for InitGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T> Drop for InitGuard<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: The value is initialized and owned by the guard.
+        unsafe { ptr::drop_in_place(self.value) };
+    }
+}
+
+/// Aborts the process when dropped.
+///
+/// Used while a pinned location is uninitialized: the location still has to be dropped by its
+/// owner, so unwinding out of it is not allowed. Panicking while unwinding aborts the process, which
+/// works without `std`.
+struct AbortOnDrop;
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        panic!("a pinned value was left uninitialized");
+    }
+}
+
+/// Drops the pinned value in `slot` and initializes a new one in its place.
+///
+/// The address of the value does not change, so this can be used to reset objects that are
+/// referenced by address, such as wait queues or locks. Structurally pinned fields can be
+/// re-initialized via the `Pin<&mut Field>` returned by the projection of [`pin_data`].
+///
+/// The pinned location must never be left uninitialized, so the process is aborted when dropping the
+/// old value panics or when `init` fails or panics. Use [`try_reinit`] to recover from errors.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// use pin_init::*;
+///
+/// #[pin_data]
+/// struct Device {
+///     #[pin]
+///     state: CMutex<u32>,
+///     resets: usize,
+/// }
+///
+/// stack_pin_init!(let device = pin_init!(Device { state <- CMutex::new(7), resets: 0 }));
+/// let this = device.as_mut().project();
+/// reinit(this.state, CMutex::new(0));
+/// *this.resets += 1;
+/// assert_eq!(*device.state.lock(), 0);
+/// ```
+pub fn reinit<T, E>(slot: Pin<&mut T>, init: impl PinInit<T, E>) {
+    // SAFETY: The value is only dropped and replaced in place, it is never moved.
+    let slot = ptr::from_mut(unsafe { Pin::into_inner_unchecked(slot) });
+    let guard = AbortOnDrop;
+    // SAFETY: The value is initialized and is replaced below before anyone can observe it again.
+    unsafe { ptr::drop_in_place(slot) };
+    // SAFETY: `slot` is valid for writes, uninitialized and pinned.
+    if unsafe { init.__pinned_init(slot) }.is_err() {
+        // Unwinding with `guard` alive aborts the process.
+        panic!("failed to re-initialize a pinned value");
+    }
+    core::mem::forget(guard);
+}
+
+/// Drops the pinned value in `slot` and initializes a new one in its place, falling back to
+/// `fallback` when `init` fails.
+///
+/// The pinned location must never be left uninitialized, so when `init` fails, the location is
+/// initialized with `fallback` and the error is returned. The process is aborted when dropping the
+/// old value, `init` or `fallback` panics.
+///
+/// See [`reinit`].
+pub fn try_reinit<T, E>(
+    slot: Pin<&mut T>,
+    init: impl PinInit<T, E>,
+    fallback: impl PinInit<T, Infallible>,
+) -> Result<(), E> {
+    // SAFETY: The value is only dropped and replaced in place, it is never moved.
+    let slot = ptr::from_mut(unsafe { Pin::into_inner_unchecked(slot) });
+    let guard = AbortOnDrop;
+    // SAFETY: The value is initialized and is replaced below before anyone can observe it again.
+    unsafe { ptr::drop_in_place(slot) };
+    // SAFETY: `slot` is valid for writes, uninitialized and pinned.
+    let res = unsafe { init.__pinned_init(slot) };
+    if res.is_err() {
+        // SAFETY: `slot` is valid for writes, uninitialized, since `init` failed, and pinned.
+        match unsafe { fallback.__pinned_init(slot) } {
+            Ok(()) => {}
+            Err(e) => match e {},
+        }
+    }
+    core::mem::forget(guard);
+    res
+}
+
 /// Trait facilitating pinned destruction.
 ///
 /// Use [`pinned_drop`] to implement this trait safely:
@@ -1630,6 +3337,9 @@ impl_zeroable! {
     // SAFETY: Type is allowed to take any value, including all zeros.
     {<T>} MaybeUninit<T>,
 
+    // SAFETY: All zeros is an empty slot, `value` is allowed to take any value.
+    {<T>} PinSlot<T>,
+
     // SAFETY: `T: Zeroable` and `UnsafeCell` is `repr(transparent)`.
     {<T: ?Sized + Zeroable>} UnsafeCell<T>,
 
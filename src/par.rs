@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Initializers for arrays and slices that initialize their elements on multiple threads.
+
+use core::{
+    num::NonZeroUsize,
+    ops::Range,
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    thread,
+};
+
+use crate::{init_from_closure, pin_init_from_closure, Init, PinInit, PinSliceBuilder};
+
+/// A pointer to the elements of a chunk that can be sent to the thread initializing them.
+struct SendPtr<T>(*mut T);
+
+// SAFETY: The elements behind the pointer are only accessed by a single thread at a time. They are
+// moved between threads, so `T: Send` is required.
+unsafe impl<T: Send> Send for SendPtr<T> {}
+
+impl<T> Clone for SendPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SendPtr<T> {}
+
+impl<T> SendPtr<T> {
+    /// Returns the pointer. Closures have to use this method, so they capture the whole `SendPtr`
+    /// instead of only the pointer.
+    fn get(self) -> *mut T {
+        self.0
+    }
+}
+
+/// Initializes the elements `range` of the slice starting at `base`.
+///
+/// Returns `Err(None)` without initializing the remaining elements when `stop` has been set by
+/// another chunk. On failure and on panic, the initialized elements of the chunk are dropped and
+/// `stop` is set.
+///
+/// # Safety
+///
+/// - the elements are valid for writes and uninitialized,
+/// - the elements are pinned, unless `I: Init<T, E>`,
+/// - no other thread accesses the elements.
+unsafe fn init_chunk<T, I, E>(
+    base: SendPtr<T>,
+    range: Range<usize>,
+    make_init: &impl Fn(usize) -> I,
+    stop: &AtomicBool,
+) -> thread::Result<Result<(), Option<E>>>
+where
+    I: PinInit<T, E>,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: `range` is inside of the slice.
+        let elements =
+            ptr::slice_from_raw_parts_mut(unsafe { base.get().add(range.start) }, range.len());
+        // SAFETY: The elements are valid for writes, uninitialized and pinned if required. No
+        // other thread accesses them.
+        let mut builder = unsafe { PinSliceBuilder::new(elements) };
+        for i in range {
+            if stop.load(Ordering::Relaxed) {
+                return Err(None);
+            }
+            builder
+                .push_pin_init(make_init(i))
+                .map_err(|(_, err)| Some(err))?;
+        }
+        builder.finish();
+        Ok(())
+    }));
+    if !matches!(result, Ok(Ok(()))) {
+        stop.store(true, Ordering::Relaxed);
+    }
+    result
+}
+
+/// Initializes the elements of `slot` in chunks on scoped threads.
+///
+/// When an element initializer fails or panics, the remaining chunks stop early and the elements
+/// of every chunk that have been initialized are dropped before the error of the first failing
+/// chunk is returned or the panic is resumed. A chunk whose thread cannot be spawned is
+/// initialized on the current thread.
+///
+/// # Safety
+///
+/// - `slot` is valid for writes and uninitialized,
+/// - `slot` is pinned, unless `I: Init<T, E>`.
+unsafe fn par_init<T, I, E>(
+    slot: *mut [T],
+    make_init: &(impl Fn(usize) -> I + Sync),
+) -> Result<(), E>
+where
+    T: Send,
+    I: PinInit<T, E>,
+    E: Send,
+{
+    let len = slot.len();
+    if len == 0 {
+        return Ok(());
+    }
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk = len.div_ceil(threads.min(len));
+    let base = SendPtr(slot.cast::<T>());
+    let stop = AtomicBool::new(false);
+    let results: Vec<_> = thread::scope(|s| {
+        let stop = &stop;
+        let mut handles = Vec::new();
+        let mut results = Vec::new();
+        for start in (0..len).step_by(chunk) {
+            let range = start..(start + chunk).min(len);
+            let spawned = thread::Builder::new().spawn_scoped(s, {
+                let range = range.clone();
+                // SAFETY: The chunk is part of `slot` and thus valid for writes, uninitialized and
+                // pinned if required. No other thread accesses it.
+                move || unsafe { init_chunk(base, range, make_init, stop) }
+            });
+            match spawned {
+                Ok(handle) => handles.push((range, handle)),
+                Err(_) => {
+                    // SAFETY: The chunk is part of `slot` and thus valid for writes, uninitialized
+                    // and pinned if required. No other thread accesses it.
+                    let result = unsafe { init_chunk(base, range.clone(), make_init, stop) };
+                    results.push((range, result));
+                }
+            }
+        }
+        for (range, handle) in handles {
+            results.push((range, handle.join().and_then(|result| result)));
+        }
+        results
+    });
+
+    let mut done = Vec::new();
+    let mut error = None;
+    let mut payload = None;
+    for (range, result) in results {
+        match result {
+            Ok(Ok(())) => done.push(range),
+            // The chunk has already dropped its elements.
+            Ok(Err(err)) => error = error.or(err),
+            Err(p) => payload = payload.or(Some(p)),
+        }
+    }
+    if let Some(payload) = payload {
+        drop_chunks(base, done);
+        panic::resume_unwind(payload);
+    }
+    match error {
+        Some(err) => {
+            drop_chunks(base, done);
+            Err(err)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Drops the elements of the initialized chunks `done` of the slice starting at `base`.
+fn drop_chunks<T>(base: SendPtr<T>, done: Vec<Range<usize>>) {
+    for range in done {
+        // SAFETY: All elements of the chunk have been initialized and are not used afterwards.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                base.get().add(range.start),
+                range.len(),
+            ))
+        };
+    }
+}
+
+/// Initializes an array by initializing each element via the provided initializer, using
+/// multiple threads.
+///
+/// The array is split into one chunk per available thread, each of which is initialized on its own
+/// scoped thread. When an element initializer fails, the other chunks stop early and the error of
+/// the first failing chunk is returned after all initialized elements have been dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pin_init::*;
+/// let array: Box<[u64; 100_000]> = Box::init(par_init_array_from_fn(|i| i as u64 * 3)).unwrap();
+/// assert_eq!(array[99_999], 299_997);
+/// ```
+pub fn par_init_array_from_fn<I, const N: usize, T, E>(
+    make_init: impl Fn(usize) -> I + Sync,
+) -> impl Init<[T; N], E>
+where
+    I: Init<T, E>,
+    T: Send,
+    E: Send,
+{
+    // SAFETY: `par_init` initializes all elements on success and leaves `slot` uninitialized on
+    // failure. `I: Init` cancels out the pinning requirement.
+    unsafe { init_from_closure(move |slot: *mut [T; N]| par_init(slot, &make_init)) }
+}
+
+/// Initializes an array by initializing each element via the provided initializer, using
+/// multiple threads.
+///
+/// See [`par_init_array_from_fn`].
+pub fn par_pin_init_array_from_fn<I, const N: usize, T, E>(
+    make_init: impl Fn(usize) -> I + Sync,
+) -> impl PinInit<[T; N], E>
+where
+    I: PinInit<T, E>,
+    T: Send,
+    E: Send,
+{
+    // SAFETY: `par_init` initializes all elements on success and leaves `slot` uninitialized on
+    // failure. `slot` is pinned.
+    unsafe { pin_init_from_closure(move |slot: *mut [T; N]| par_init(slot, &make_init)) }
+}
+
+/// Initializes a slice of length `len` by initializing each element via the provided initializer,
+/// using multiple threads.
+///
+/// See [`par_init_array_from_fn`].
+///
+/// # Panics
+///
+/// Panics when the initializer is used on a slice whose length is not `len`.
+pub fn par_init_slice_from_fn<I, T, E>(
+    len: usize,
+    make_init: impl Fn(usize) -> I + Sync,
+) -> impl Init<[T], E>
+where
+    I: Init<T, E>,
+    T: Send,
+    E: Send,
+{
+    // SAFETY: `par_init` initializes all elements on success and leaves `slot` uninitialized on
+    // failure. `I: Init` cancels out the pinning requirement.
+    unsafe {
+        init_from_closure(move |slot: *mut [T]| {
+            assert_eq!(
+                slot.len(),
+                len,
+                "the slice initializer has to be used on a slice of the same length"
+            );
+            par_init(slot, &make_init)
+        })
+    }
+}
+
+/// Initializes a slice of length `len` by initializing each element via the provided initializer,
+/// using multiple threads.
+///
+/// See [`par_init_array_from_fn`].
+///
+/// # Panics
+///
+/// Panics when the initializer is used on a slice whose length is not `len`.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// # #[path = "../examples/mutex.rs"] mod mutex; use mutex::*;
+/// # use pin_init::*;
+/// # use core::pin::Pin;
+/// # #[cfg(feature = "dst")] {
+/// let slice: Pin<Box<[CMutex<usize>]>> =
+///     Box::pin_init_dst(1_000, par_pin_init_slice_from_fn(1_000, CMutex::new)).unwrap();
+/// assert_eq!(*slice[999].lock(), 999);
+/// # }
+/// ```
+pub fn par_pin_init_slice_from_fn<I, T, E>(
+    len: usize,
+    make_init: impl Fn(usize) -> I + Sync,
+) -> impl PinInit<[T], E>
+where
+    I: PinInit<T, E>,
+    T: Send,
+    E: Send,
+{
+    // SAFETY: `par_init` initializes all elements on success and leaves `slot` uninitialized on
+    // failure. `slot` is pinned.
+    unsafe {
+        pin_init_from_closure(move |slot: *mut [T]| {
+            assert_eq!(
+                slot.len(),
+                len,
+                "the slice initializer has to be used on a slice of the same length"
+            );
+            par_init(slot, &make_init)
+        })
+    }
+}
@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Slot poisoning of the `debug-poison` feature.
+//!
+//! Before an initializer runs, its slot is filled with [`POISON`]. After the initializer
+//! succeeded, the fields of the slot are checked if its type has generated field metadata (see
+//! [`FieldMeta`]). This is done for the slots of [`InPlaceInit`], [`InPlaceWrite`] and
+//! [`StackInit`] by [`checked`] and [`checked_init`], and for every field initialized with `<-` in
+//! [`pin_init!`] and [`init!`] by [`poison_field`] and [`check_field`].
+//!
+//! Whether a type has field metadata is decided with `min_specialization`, [`FieldMeta`] is a
+//! specialization trait for that reason. Its implementations are generated through
+//! [`__field_meta!`], which is allowed to use the feature in the crates of the users.
+//!
+//! Only fields whose type is known to have no uninitialized bytes (see [`NoUninit`]) are checked,
+//! since padding or a `MaybeUninit` may legitimately stay uninitialized. Fields smaller than two
+//! bytes are not checked, since they too often legitimately contain the pattern.
+//!
+//! [`InPlaceInit`]: crate::InPlaceInit
+//! [`InPlaceWrite`]: crate::InPlaceWrite
+//! [`StackInit`]: crate::__internal::StackInit
+//! [`pin_init!`]: crate::pin_init!
+//! [`init!`]: crate::init!
+//! [`__field_meta!`]: crate::__field_meta!
+
+#[cfg(feature = "debug-poison")]
+use core::{mem::size_of, ptr::NonNull};
+
+#[cfg(feature = "debug-poison")]
+use crate::__internal::PhantomInvariant;
+use crate::{Init, PinInit};
+#[cfg(feature = "debug-poison")]
+use specialize::{CheckFields, Poison};
+
+// The `default` items of the specializing implementations are gated before `cfg` is evaluated, so
+// they live in a module that is only parsed with the feature.
+#[cfg(feature = "debug-poison")]
+mod specialize;
+
+/// The byte pattern that slots are filled with.
+#[cfg(feature = "debug-poison")]
+pub(crate) const POISON: u8 = 0xA5;
+
+/// Wraps `init` such that it poisons its slot and checks it after successful initialization.
+#[cfg(feature = "debug-poison")]
+#[inline]
+pub(crate) fn checked<T: ?Sized, E>(init: impl PinInit<T, E>) -> impl PinInit<T, E> {
+    Poisoned(init, PhantomInvariant::new())
+}
+
+/// Wraps `init` such that it poisons its slot and checks it after successful initialization.
+#[cfg(feature = "debug-poison")]
+#[inline]
+pub(crate) fn checked_init<T: ?Sized, E>(init: impl Init<T, E>) -> impl Init<T, E> {
+    Poisoned(init, PhantomInvariant::new())
+}
+
+/// Wraps `init` such that it poisons its slot and checks it after successful initialization.
+#[cfg(not(feature = "debug-poison"))]
+#[inline(always)]
+pub(crate) fn checked<T: ?Sized, E>(init: impl PinInit<T, E>) -> impl PinInit<T, E> {
+    init
+}
+
+/// Wraps `init` such that it poisons its slot and checks it after successful initialization.
+#[cfg(not(feature = "debug-poison"))]
+#[inline(always)]
+pub(crate) fn checked_init<T: ?Sized, E>(init: impl Init<T, E>) -> impl Init<T, E> {
+    init
+}
+
+/// An initializer returned by [`checked`] and [`checked_init`].
+#[cfg(feature = "debug-poison")]
+pub(crate) struct Poisoned<I, T: ?Sized, E>(I, PhantomInvariant<(E, T)>);
+
+// SAFETY: The `__pinned_init` function delegates to `self.0` after poisoning `slot`, so it
+// - returns `Ok(())` on successful initialization,
+// - returns `Err(err)` on error and in this case `slot` will be dropped.
+// - considers `slot` pinned.
+#[cfg(feature = "debug-poison")]
+unsafe impl<T: ?Sized, E, I: PinInit<T, E>> PinInit<T, E> for Poisoned<I, T, E> {
+    unsafe fn __pinned_init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: `slot` is valid for writes and uninitialized.
+        unsafe { poison_field(slot) };
+        // SAFETY: All requirements fulfilled since this function is `__pinned_init`.
+        unsafe { self.0.__pinned_init(slot)? };
+        // SAFETY: `slot` has been poisoned above and then initialized.
+        unsafe { check_field(slot) };
+        Ok(())
+    }
+}
+
+// SAFETY: The `__init` function delegates to `self.0` after poisoning `slot`, so it
+// - returns `Ok(())` on successful initialization,
+// - returns `Err(err)` on error and in this case `slot` will be dropped.
+#[cfg(feature = "debug-poison")]
+unsafe impl<T: ?Sized, E, I: Init<T, E>> Init<T, E> for Poisoned<I, T, E> {
+    unsafe fn __init(self, slot: *mut T) -> Result<(), E> {
+        // SAFETY: `slot` is valid for writes and uninitialized.
+        unsafe { poison_field(slot) };
+        // SAFETY: All requirements fulfilled since this function is `__init`.
+        unsafe { self.0.__init(slot)? };
+        // SAFETY: `slot` has been poisoned above and then initialized.
+        unsafe { check_field(slot) };
+        Ok(())
+    }
+}
+
+/// Fills `slot` with the poison pattern if its size is known, which for unsized slots requires
+/// the `dst` feature.
+///
+/// # Safety
+///
+/// `slot` is valid for writes and uninitialized.
+#[cfg(feature = "debug-poison")]
+#[inline]
+pub unsafe fn poison_field<T: ?Sized>(slot: *mut T) {
+    // SAFETY: `slot` is valid for writes and uninitialized.
+    unsafe { T::poison(slot) };
+}
+
+/// Panics when a field of `*slot` still only contains the poison pattern, if the type of `*slot` has field
+/// metadata.
+///
+/// # Safety
+///
+/// `slot` is valid for reads and has been poisoned by [`poison_field`] before being initialized.
+#[cfg(feature = "debug-poison")]
+#[inline]
+pub unsafe fn check_field<T: ?Sized>(slot: *const T) {
+    // SAFETY: `slot` is valid for reads and has been poisoned before being initialized.
+    unsafe {
+        <T as CheckFields<PinDataSource>>::check(slot);
+        <T as CheckFields<ZeroableSource>>::check(slot);
+    }
+}
+
+/// Field metadata of a struct, generated by `#[pin_data]` (with `S` = [`PinDataSource`]) and by
+/// `#[derive(Zeroable)]` and `#[derive(MaybeZeroable)]` (with `S` = [`ZeroableSource`]).
+///
+/// The source is part of the trait, so a struct using both does not get conflicting
+/// implementations. Implementations are only allowed through [`__field_meta!`].
+///
+/// # Safety
+///
+/// `__poisoned_fields` only reads the fields of `*slot`.
+///
+/// [`__field_meta!`]: crate::__field_meta!
+#[cfg(feature = "debug-poison")]
+#[rustc_specialization_trait]
+pub unsafe trait FieldMeta<S> {
+    /// Calls `f` with the name of every field of `*slot` that still only contains the poison
+    /// pattern.
+    ///
+    /// # Safety
+    ///
+    /// `slot` is valid for reads and has been poisoned before being initialized.
+    unsafe fn __poisoned_fields(slot: *const Self, f: &mut dyn FnMut(&'static str));
+}
+
+/// Marks the [`FieldMeta`] generated by `#[pin_data]`.
+#[cfg(feature = "debug-poison")]
+pub struct PinDataSource;
+
+/// Marks the [`FieldMeta`] generated by `#[derive(Zeroable)]` and `#[derive(MaybeZeroable)]`.
+#[cfg(feature = "debug-poison")]
+pub struct ZeroableSource;
+
+/// Types whose values do not contain any uninitialized bytes.
+///
+/// # Safety
+///
+/// Every byte of every value of the type is initialized.
+#[cfg(feature = "debug-poison")]
+pub unsafe trait NoUninit {}
+
+#[cfg(feature = "debug-poison")]
+macro_rules! impl_no_uninit {
+    ($($({$($generics:tt)*})? $t:ty),* $(,)?) => {
+        // SAFETY: These types do not have padding bytes and all of their bytes are initialized.
+        $(unsafe impl$($($generics)*)? NoUninit for $t {})*
+    };
+}
+
+#[cfg(feature = "debug-poison")]
+impl_no_uninit! {
+    bool, char,
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    f32, f64,
+    {<T: ?Sized>} *const T,
+    {<T: ?Sized>} *mut T,
+    {<T: ?Sized>} NonNull<T>,
+    {<T: ?Sized>} &T,
+    {<T: ?Sized>} &mut T,
+    {<T: NoUninit, const N: usize>} [T; N],
+}
+
+/// Looks up whether the field at the pointer can be checked, see the module documentation.
+///
+/// Used by the [`FieldMeta`] implementations as `(&&FieldProbe::new(ptr)).__is_poisoned()`. A
+/// method of a trait implemented for `&FieldProbe<T>` is preferred over one implemented for
+/// `FieldProbe<T>`, so fields of generic types are not checked unless their bounds imply
+/// [`NoUninit`].
+#[cfg(feature = "debug-poison")]
+pub struct FieldProbe<T: ?Sized>(*const T);
+
+#[cfg(feature = "debug-poison")]
+impl<T: ?Sized> FieldProbe<T> {
+    /// Creates a probe for the field at `field`.
+    #[inline(always)]
+    pub fn new(field: *const T) -> Self {
+        Self(field)
+    }
+}
+
+/// Chosen by [`FieldProbe`] for fields without uninitialized bytes.
+#[cfg(feature = "debug-poison")]
+pub trait NoUninitProbe {
+    /// Returns `true` if the field is at least two bytes long and only contains the poison
+    /// pattern.
+    ///
+    /// # Safety
+    ///
+    /// The field is valid for reads and has been poisoned before being initialized.
+    unsafe fn __is_poisoned(&self) -> bool;
+}
+
+#[cfg(feature = "debug-poison")]
+impl<T: NoUninit> NoUninitProbe for &FieldProbe<T> {
+    #[inline]
+    unsafe fn __is_poisoned(&self) -> bool {
+        let start = self.0.cast::<u8>();
+        // SAFETY: The field is valid for reads and all of its bytes are initialized, either by
+        // the initializer, or by poisoning it.
+        size_of::<T>() >= 2 && (0..size_of::<T>()).all(|i| unsafe { start.add(i).read() } == POISON)
+    }
+}
+
+/// Chosen by [`FieldProbe`] for all other fields, which are not checked.
+#[cfg(feature = "debug-poison")]
+pub trait UncheckedProbe {
+    /// Returns `false`.
+    ///
+    /// # Safety
+    ///
+    /// The field is valid for reads and has been poisoned before being initialized.
+    unsafe fn __is_poisoned(&self) -> bool;
+}
+
+#[cfg(feature = "debug-poison")]
+impl<T: ?Sized> UncheckedProbe for FieldProbe<T> {
+    #[inline(always)]
+    unsafe fn __is_poisoned(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The specializing implementations behind [`poison_field`] and [`check_field`].
+//!
+//! [`poison_field`]: super::poison_field
+//! [`check_field`]: super::check_field
+
+use core::{any::type_name, mem::size_of};
+
+use super::{FieldMeta, POISON};
+
+pub(super) trait Poison {
+    /// # Safety
+    ///
+    /// `slot` is valid for writes and uninitialized.
+    unsafe fn poison(slot: *mut Self);
+}
+
+impl<T: ?Sized> Poison for T {
+    // The size of an unsized slot is only known with `layout_for_ptr`.
+    #[cfg(not(feature = "dst"))]
+    default unsafe fn poison(_slot: *mut T) {}
+
+    #[cfg(feature = "dst")]
+    default unsafe fn poison(slot: *mut T) {
+        // SAFETY: `slot` is valid for writes, so its metadata describes a valid value.
+        let size = unsafe { core::mem::size_of_val_raw(slot) };
+        // SAFETY: `slot` is valid for writes of `size` bytes and uninitialized.
+        unsafe { slot.cast::<u8>().write_bytes(POISON, size) };
+    }
+}
+
+impl<T> Poison for T {
+    unsafe fn poison(slot: *mut T) {
+        // SAFETY: `slot` is valid for writes and uninitialized.
+        unsafe { slot.cast::<u8>().write_bytes(POISON, size_of::<T>()) };
+    }
+}
+
+pub(super) trait CheckFields<S> {
+    /// # Safety
+    ///
+    /// `slot` is valid for reads and has been poisoned before being initialized.
+    unsafe fn check(slot: *const Self);
+}
+
+impl<T: ?Sized, S> CheckFields<S> for T {
+    // Without field metadata there is nothing to check; a whole value consisting of the pattern is
+    // too often legitimate, e.g. a byte array.
+    default unsafe fn check(_slot: *const T) {}
+}
+
+impl<T: ?Sized + FieldMeta<S>, S> CheckFields<S> for T {
+    unsafe fn check(slot: *const T) {
+        let mut report = |name: &'static str| {
+            panic!(
+                "field `{name}` of `{}` is still poisoned after initialization",
+                type_name::<T>()
+            );
+        };
+        // SAFETY: `slot` is valid for reads and has been poisoned before being initialized.
+        unsafe { T::__poisoned_fields(slot, &mut report) };
+    }
+}
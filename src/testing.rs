@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Utilities for testing the cleanup paths of initializers.
+//!
+//! Initializers have to drop everything they initialized when they fail or panic part-way through.
+//! These paths are rarely taken in practice, so this module provides tools to exercise them:
+//!
+//! - [`FailAfter`] injects an error or a panic into the nth initializer it wraps, where n is a
+//!   const generic parameter, and [`FaultInjector`] does the same with n chosen at runtime,
+//! - [`DropTracker`] hands out [`Tracked`] values and detects values that are leaked or dropped
+//!   twice,
+//! - [`assert_pin_init_cleanup`] and [`assert_init_cleanup`] combine both, injecting a fault at
+//!   every step of an initializer in turn.
+//!
+//! This module is only available with the `testing` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use pin_init::{testing::*, *};
+//!
+//! struct Pair {
+//!     a: Tracked,
+//!     b: Tracked,
+//! }
+//!
+//! let steps = assert_init_cleanup(|fail, tracker| {
+//!     init!(Pair {
+//!         a <- fail.wrap_init(Ok::<_, InjectedFault>(tracker.track())),
+//!         b <- fail.wrap_init(Ok::<_, InjectedFault>(tracker.track())),
+//!     }? InjectedFault)
+//! });
+//! assert_eq!(steps, 2);
+//! ```
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use crate::{init_from_closure, pin_init_from_closure, InPlaceWrite, Init, PinInit};
+
+/// The kind of fault injected by [`FailAfter`] and [`FaultInjector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The initializer returns [`InjectedFault`] converted into its error type.
+    Error,
+    /// The initializer panics.
+    Panic,
+}
+
+/// The error returned by an initializer wrapped by [`FailAfter`] or [`FaultInjector`] when the
+/// fault is injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedFault;
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("injected fault")
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+struct FaultInjectorInner {
+    fail_at: Option<usize>,
+    fault: Fault,
+    steps: AtomicUsize,
+}
+
+/// Injects a fault into the nth initializer it wraps.
+///
+/// Every initializer wrapped by [`wrap`] or [`wrap_init`] counts as one step when it runs. The
+/// step with index `n` (counting from zero) fails or panics before running the wrapped initializer,
+/// all other steps run the wrapped initializer unchanged.
+///
+/// `FaultInjector` is a handle; clones share the same step counter. Use [`FailAfter`] when `n` is
+/// known at compile time.
+///
+/// [`wrap`]: FaultInjector::wrap
+/// [`wrap_init`]: FaultInjector::wrap_init
+#[derive(Clone)]
+pub struct FaultInjector {
+    inner: Arc<FaultInjectorInner>,
+}
+
+impl FaultInjector {
+    /// Creates a new `FaultInjector` that injects `fault` into step `n`.
+    pub fn new(n: usize, fault: Fault) -> Self {
+        Self::with(Some(n), fault)
+    }
+
+    /// Creates a new `FaultInjector` that never injects a fault and only counts the steps.
+    pub fn never() -> Self {
+        Self::with(None, Fault::Error)
+    }
+
+    fn with(fail_at: Option<usize>, fault: Fault) -> Self {
+        Self {
+            inner: Arc::new(FaultInjectorInner {
+                fail_at,
+                fault,
+                steps: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Returns the number of steps that have run so far, including the failing one.
+    pub fn steps(&self) -> usize {
+        self.inner.steps.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the fault has been injected.
+    pub fn triggered(&self) -> bool {
+        self.inner.fail_at.is_some_and(|n| n < self.steps())
+    }
+
+    fn step<E: From<InjectedFault>>(&self) -> Result<(), E> {
+        let step = self.inner.steps.fetch_add(1, Ordering::Relaxed);
+        if self.inner.fail_at == Some(step) {
+            match self.inner.fault {
+                Fault::Error => return Err(InjectedFault.into()),
+                Fault::Panic => panic!("{}", injected_panic_message(step)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Wraps a pin-initializer, making it one step of this `FaultInjector`.
+    pub fn wrap<T, E, I>(&self, init: I) -> impl PinInit<T, E> + use<T, E, I>
+    where
+        I: PinInit<T, E>,
+        E: From<InjectedFault>,
+    {
+        let this = self.clone();
+        // SAFETY: When the fault is injected, the slot is not touched. Otherwise `init` is called
+        // with the same slot, so its guarantees carry over.
+        unsafe {
+            pin_init_from_closure(move |slot| {
+                this.step()?;
+                init.__pinned_init(slot)
+            })
+        }
+    }
+
+    /// Wraps an initializer, making it one step of this `FaultInjector`.
+    pub fn wrap_init<T, E, I>(&self, init: I) -> impl Init<T, E> + use<T, E, I>
+    where
+        I: Init<T, E>,
+        E: From<InjectedFault>,
+    {
+        let this = self.clone();
+        // SAFETY: When the fault is injected, the slot is not touched. Otherwise `init` is called
+        // with the same slot, so its guarantees carry over.
+        unsafe {
+            init_from_closure(move |slot| {
+                this.step()?;
+                init.__init(slot)
+            })
+        }
+    }
+}
+
+/// Injects a fault into the `N`th initializer it wraps.
+///
+/// This is a [`FaultInjector`] whose failing step is fixed at compile time; steps are counted from
+/// zero as well. The helpers that inject a fault at every step, such as
+/// [`assert_pin_init_cleanup`], choose the step at runtime and thus use [`FaultInjector`].
+///
+/// `FailAfter` is a handle; clones share the same step counter.
+///
+/// # Examples
+///
+/// ```rust
+/// use pin_init::{testing::*, *};
+///
+/// let fail = FailAfter::<1>::new(Fault::Error);
+/// let init = init_array_from_fn::<_, 3, u32, InjectedFault>(|i| {
+///     fail.wrap_init(Ok::<_, InjectedFault>(i as u32))
+/// });
+/// stack_try_pin_init!(let array = init);
+/// assert_eq!(array.err(), Some(InjectedFault));
+/// assert_eq!(fail.steps(), 2);
+/// ```
+#[derive(Clone)]
+pub struct FailAfter<const N: usize> {
+    injector: FaultInjector,
+}
+
+impl<const N: usize> FailAfter<N> {
+    /// Creates a new `FailAfter` that injects `fault` into step `N`.
+    pub fn new(fault: Fault) -> Self {
+        Self {
+            injector: FaultInjector::new(N, fault),
+        }
+    }
+
+    /// Returns the number of steps that have run so far, including the failing one.
+    pub fn steps(&self) -> usize {
+        self.injector.steps()
+    }
+
+    /// Returns `true` if the fault has been injected.
+    pub fn triggered(&self) -> bool {
+        self.injector.triggered()
+    }
+
+    /// Wraps a pin-initializer, making it one step of this `FailAfter`.
+    pub fn wrap<T, E, I>(&self, init: I) -> impl PinInit<T, E> + use<T, E, I, N>
+    where
+        I: PinInit<T, E>,
+        E: From<InjectedFault>,
+    {
+        self.injector.wrap(init)
+    }
+
+    /// Wraps an initializer, making it one step of this `FailAfter`.
+    pub fn wrap_init<T, E, I>(&self, init: I) -> impl Init<T, E> + use<T, E, I, N>
+    where
+        I: Init<T, E>,
+        E: From<InjectedFault>,
+    {
+        self.injector.wrap_init(init)
+    }
+
+    /// Returns the underlying [`FaultInjector`].
+    pub fn into_injector(self) -> FaultInjector {
+        self.injector
+    }
+}
+
+#[derive(Default)]
+struct TrackerState {
+    live: Vec<bool>,
+    drop_order: Vec<usize>,
+    double_drops: Vec<usize>,
+}
+
+/// Records the creation and destruction of [`Tracked`] values.
+///
+/// Each value handed out by [`track`] has a unique id, counting up from zero. When the value is
+/// dropped, its id is appended to the [drop order]. Values that are dropped twice are recorded as
+/// well, instead of panicking inside of `drop`.
+///
+/// `DropTracker` is a handle; clones share the same records.
+///
+/// [`track`]: DropTracker::track
+/// [drop order]: DropTracker::drop_order
+#[derive(Clone, Default)]
+pub struct DropTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+impl DropTracker {
+    /// Creates a new tracker without any values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, TrackerState> {
+        // A panic while the lock is held cannot leave the state inconsistent.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Creates a new tracked value.
+    pub fn track(&self) -> Tracked {
+        let mut state = self.state();
+        let id = state.live.len();
+        state.live.push(true);
+        Tracked {
+            tracker: self.clone(),
+            id,
+        }
+    }
+
+    /// Returns the number of tracked values that have been created.
+    pub fn created(&self) -> usize {
+        self.state().live.len()
+    }
+
+    /// Returns the ids of the tracked values that have not been dropped yet.
+    pub fn live(&self) -> Vec<usize> {
+        let state = self.state();
+        (0..state.live.len()).filter(|&id| state.live[id]).collect()
+    }
+
+    /// Returns the ids of the dropped values in the order they were dropped.
+    pub fn drop_order(&self) -> Vec<usize> {
+        self.state().drop_order.clone()
+    }
+
+    /// Returns the ids of the values that have been dropped more than once.
+    pub fn double_drops(&self) -> Vec<usize> {
+        self.state().double_drops.clone()
+    }
+
+    /// Asserts that no value has been dropped more than once.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a value has been dropped more than once.
+    #[track_caller]
+    pub fn assert_no_double_drop(&self) {
+        let double_drops = self.double_drops();
+        assert!(
+            double_drops.is_empty(),
+            "tracked values have been dropped more than once: {double_drops:?}"
+        );
+    }
+
+    /// Asserts that every value has been dropped exactly once.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a value has been leaked or has been dropped more than once.
+    #[track_caller]
+    pub fn assert_all_dropped(&self) {
+        self.assert_no_double_drop();
+        let live = self.live();
+        assert!(live.is_empty(), "tracked values have been leaked: {live:?}");
+    }
+}
+
+/// A value whose drop is recorded by a [`DropTracker`].
+pub struct Tracked {
+    tracker: DropTracker,
+    id: usize,
+}
+
+impl Tracked {
+    /// Returns the id of this value.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl fmt::Debug for Tracked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Tracked").field(&self.id).finish()
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        let mut state = self.tracker.state();
+        if state.live[self.id] {
+            state.live[self.id] = false;
+            state.drop_order.push(self.id);
+        } else {
+            state.double_drops.push(self.id);
+        }
+    }
+}
+
+/// Runs the initializers returned by `make_init` with a fault injected at every step in turn and
+/// asserts that no [`Tracked`] value is leaked or dropped twice.
+///
+/// `make_init` is called with handles to a [`FaultInjector`] and a [`DropTracker`], both new for
+/// every run. The returned initializer is run in a new allocation and, if it succeeds, the value is
+/// dropped afterwards. Every tracked value must then have been dropped exactly once.
+///
+/// The first run does not inject a fault and determines the number of steps. Then, for both kinds of
+/// [`Fault`], one run is made for every step with the fault injected at that step.
+///
+/// Returns the number of steps of the initializer.
+///
+/// # Panics
+///
+/// Panics when a tracked value has been leaked or dropped twice, when the initializer fails without
+/// an injected fault or when the number of steps changes between runs. Panics that are not
+/// injected are propagated.
+#[track_caller]
+pub fn assert_pin_init_cleanup<T, E, I>(
+    mut make_init: impl FnMut(FaultInjector, DropTracker) -> I,
+) -> usize
+where
+    I: PinInit<T, E>,
+    E: fmt::Debug,
+{
+    assert_cleanup(|fail, tracker| {
+        Box::<T>::new_uninit()
+            .write_pin_init(make_init(fail.clone(), tracker.clone()))
+            .map(drop)
+    })
+}
+
+/// Runs the initializers returned by `make_init` with a fault injected at every step in turn and
+/// asserts that no [`Tracked`] value is leaked or dropped twice.
+///
+/// See [`assert_pin_init_cleanup`].
+#[track_caller]
+pub fn assert_init_cleanup<T, E, I>(
+    mut make_init: impl FnMut(FaultInjector, DropTracker) -> I,
+) -> usize
+where
+    I: Init<T, E>,
+    E: fmt::Debug,
+{
+    assert_cleanup(|fail, tracker| {
+        Box::<T>::new_uninit()
+            .write_init(make_init(fail.clone(), tracker.clone()))
+            .map(drop)
+    })
+}
+
+#[track_caller]
+fn assert_cleanup<E: fmt::Debug>(
+    mut run: impl FnMut(&FaultInjector, &DropTracker) -> Result<(), E>,
+) -> usize {
+    let steps = {
+        let fail = FaultInjector::never();
+        let tracker = DropTracker::new();
+        if let Err(e) = run(&fail, &tracker) {
+            panic!("the initializer failed without an injected fault: {e:?}");
+        }
+        tracker.assert_all_dropped();
+        fail.steps()
+    };
+    for fault in [Fault::Error, Fault::Panic] {
+        for n in 0..steps {
+            let fail = FaultInjector::new(n, fault);
+            let tracker = DropTracker::new();
+            let res = panic::catch_unwind(AssertUnwindSafe(|| run(&fail, &tracker)));
+            if let Err(payload) = res {
+                if matches!(fault, Fault::Error) || !is_injected_panic(&*payload, n) {
+                    panic::resume_unwind(payload);
+                }
+            }
+            assert!(
+                fail.triggered(),
+                "step {n} was not reached, the number of steps changed between runs"
+            );
+            tracker.assert_all_dropped();
+        }
+    }
+    steps
+}
+
+fn injected_panic_message(step: usize) -> String {
+    format!("injected panic at step {step}")
+}
+
+/// Returns `true` if `payload` is the panic injected by a [`FaultInjector`] at `step`.
+fn is_injected_panic(payload: &(dyn Any + Send), step: usize) -> bool {
+    payload
+        .downcast_ref::<String>()
+        .is_some_and(|msg| *msg == injected_panic_message(step))
+}
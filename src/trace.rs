@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tracing of the field initializations done by [`init!`] and [`pin_init!`].
+//!
+//! When a hook is installed with [`set_hook`], it is called with an [`Event`] whenever the
+//! initializer macros start initializing a field, finish it, fail, or drop an already initialized
+//! field because a later one failed. Initializers nested in fields increase the [depth] of their
+//! events, so a hook can reconstruct the tree of initializations and measure the time spent in
+//! each of them.
+//!
+//! This module is only available with the `trace` feature. Without it, the initializer macros do
+//! not generate any tracing code.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use pin_init::{trace::*, *};
+//!
+//! fn print(event: Event) {
+//!     println!(
+//!         "{:indent$}{:?} {}::{}",
+//!         "",
+//!         event.phase,
+//!         event.ty,
+//!         event.field,
+//!         indent = 2 * event.depth,
+//!     );
+//! }
+//!
+//! #[pin_data]
+//! struct Point {
+//!     x: u32,
+//!     y: u32,
+//! }
+//!
+//! set_hook(print);
+//! stack_pin_init!(let point = pin_init!(Point { x: 1, y: 2 }));
+//! clear_hook();
+//! assert_eq!((point.x, point.y), (1, 2));
+//! ```
+//!
+//! [depth]: Event::depth
+//! [`init!`]: crate::init!
+//! [`pin_init!`]: crate::pin_init!
+
+use core::{
+    any::type_name,
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// The phase of a field initialization reported by an [`Event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The initialization of the field is about to start.
+    Start,
+    /// The field has been initialized.
+    Success,
+    /// The initializer of the field returned an error or panicked.
+    Failure,
+    /// The field had been initialized, but is dropped since the initialization of a later field
+    /// failed.
+    Rollback,
+}
+
+/// A field initialization event passed to the hook installed with [`set_hook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// The name of the type whose field is initialized.
+    pub ty: &'static str,
+    /// The name of the field.
+    pub field: &'static str,
+    /// The phase of the initialization.
+    pub phase: Phase,
+    /// The number of field initializations that enclose this one.
+    pub depth: usize,
+}
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Installs `hook`, replacing the previous one.
+///
+/// The hook is global and called from every thread that initializes fields.
+pub fn set_hook(hook: fn(Event)) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Removes the installed hook.
+pub fn clear_hook() {
+    HOOK.store(ptr::null_mut(), Ordering::Release);
+}
+
+fn emit(event: Event) {
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: All non-null values stored in `HOOK` are `fn(Event)` pointers.
+        let hook = unsafe { mem::transmute::<*mut (), fn(Event)>(hook) };
+        hook(event);
+    }
+}
+
+#[cfg(feature = "std")]
+mod depth {
+    use core::cell::Cell;
+
+    std::thread_local! {
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(super) fn enter() -> usize {
+        DEPTH.with(|depth| depth.replace(depth.get() + 1))
+    }
+
+    pub(super) fn exit() {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+// Without thread locals, the depth is shared by all threads.
+#[cfg(not(feature = "std"))]
+mod depth {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+    pub(super) fn enter() -> usize {
+        DEPTH.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(super) fn exit() {
+        DEPTH.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A field whose initialization is traced.
+#[derive(Clone, Copy)]
+pub(crate) struct Field {
+    ty: &'static str,
+    field: &'static str,
+    depth: usize,
+}
+
+impl Field {
+    /// Reports the start of the initialization of `field` of `T`.
+    pub(crate) fn start<T: ?Sized>(field: &'static str) -> Self {
+        let this = Self {
+            ty: type_name::<T>(),
+            field,
+            depth: depth::enter(),
+        };
+        this.emit(Phase::Start);
+        this
+    }
+
+    /// Reports the end of the initialization with `phase`.
+    pub(crate) fn finish(self, phase: Phase) {
+        depth::exit();
+        self.emit(phase);
+    }
+
+    pub(crate) fn emit(self, phase: Phase) {
+        emit(Event {
+            ty: self.ty,
+            field: self.field,
+            phase,
+            depth: self.depth,
+        });
+    }
+}
@@ -0,0 +1,167 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(any(feature = "std", feature = "alloc"))]
+
+use core::{
+    cell::Cell,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+#[cfg(feature = "alloc")]
+impl From<core::alloc::AllocError> for Error {
+    fn from(_: core::alloc::AllocError) -> Self {
+        Self(u32::MAX)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl From<core::convert::Infallible> for Error {
+    fn from(e: core::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
+#[pin_data]
+struct Foo {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[test]
+fn heterogeneous_pin_inits() {
+    let offset = 10;
+    let inits: Vec<BoxedPinInit<'_, Foo, Error>> = vec![
+        BoxedPinInit::new(pin_init!(Foo {
+            value: 1,
+            _pin: PhantomPinned,
+        }? Error)),
+        BoxedPinInit::new(pin_init_scope(move || {
+            Ok(pin_init!(Foo {
+                value: offset,
+                _pin: PhantomPinned,
+            }? Error))
+        })),
+        BoxedPinInit::new(Err(Error(3))),
+    ];
+    let results: Vec<_> = inits
+        .into_iter()
+        .map(|init| {
+            let foo: Result<Pin<Box<Foo>>, Error> = Box::try_pin_init(init);
+            foo.map(|foo| foo.value)
+        })
+        .collect();
+    assert_eq!(results, [Ok(1), Ok(10), Err(Error(3))]);
+}
+
+#[test]
+fn boxed_init_borrows() {
+    struct Bar {
+        value: u32,
+    }
+
+    let calls = Cell::new(0);
+    let calls = &calls;
+    let make = |value| {
+        BoxedInit::new(init_scope(move || {
+            calls.set(calls.get() + 1);
+            Ok::<_, Error>(init!(Bar { value }? Error))
+        }))
+    };
+    let inits = [make(1), make(2)];
+    assert_eq!(calls.get(), 0);
+    let values: Vec<_> = inits
+        .into_iter()
+        .map(|init| {
+            stack_try_pin_init!(let bar: Bar = init);
+            bar.unwrap().value
+        })
+        .collect();
+    assert_eq!(values, [1, 2]);
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn unused_init_is_dropped() {
+    struct CountDrop<'a>(&'a AtomicUsize);
+
+    impl Drop for CountDrop<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let drops = AtomicUsize::new(0);
+    let value = CountDrop(&drops);
+    let init: BoxedPinInit<'_, CountDrop<'_>> = BoxedPinInit::new(value);
+    drop(init);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+trait Plugin {
+    fn id(&self) -> u32;
+}
+
+impl Plugin for Foo {
+    fn id(&self) -> u32 {
+        self.value
+    }
+}
+
+#[pin_data]
+struct Bar {
+    #[pin]
+    inner: Foo,
+}
+
+impl Plugin for Bar {
+    fn id(&self) -> u32 {
+        self.inner.value * 100
+    }
+}
+
+#[test]
+fn type_erased_target() {
+    let pending: Vec<BoxedDynPinInit<'_, dyn Plugin, Error>> = vec![
+        BoxedDynPinInit::try_new(
+            pin_init!(Foo {
+                value: 1,
+                _pin: PhantomPinned,
+            }? Error),
+            |foo| foo,
+        ),
+        BoxedDynPinInit::try_new(
+            pin_init!(Bar {
+                inner <- pin_init!(Foo {
+                    value: 2,
+                    _pin: PhantomPinned,
+                }? Error),
+            }? Error),
+            |bar| bar,
+        ),
+        BoxedDynPinInit::try_new(Err::<Foo, _>(Error(3)), |foo| foo),
+    ];
+    let ids: Vec<_> = pending
+        .into_iter()
+        .map(|init| init.pin_init().map(|plugin| plugin.id()))
+        .collect();
+    assert_eq!(ids, [Ok(1), Ok(200), Err(Error(3))]);
+}
+
+#[test]
+fn type_erased_infallible() {
+    let plugin = BoxedDynPinInit::<dyn Plugin>::new(
+        pin_init!(Foo {
+            value: 7,
+            _pin: PhantomPinned,
+        }),
+        |foo| foo,
+    );
+    assert_eq!(plugin.pin_init().unwrap().id(), 7);
+}
@@ -0,0 +1,70 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(feature = "std")]
+
+use std::rc::Rc;
+
+use pin_init::*;
+
+fn chained(value: &Rc<()>, fail: Option<bool>) -> impl PinInit<Rc<()>, i32> {
+    Ok(value.clone()).pin_chain(move |_| match fail {
+        None => Ok(()),
+        Some(false) => Err(1),
+        Some(true) => panic!("chain panicked"),
+    })
+}
+
+#[test]
+fn success() {
+    let rc = Rc::new(());
+    {
+        stack_try_pin_init!(let value: Rc<()> = chained(&rc, None).catch_unwind());
+        assert!(value.is_ok());
+        assert_eq!(Rc::strong_count(&rc), 2);
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn error() {
+    let rc = Rc::new(());
+    stack_try_pin_init!(let value: Rc<()> = chained(&rc, Some(false)).catch_unwind());
+    match value {
+        Err(InitPanic::Error(err)) => assert_eq!(err, 1),
+        _ => panic!("expected an error"),
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn panic() {
+    let rc = Rc::new(());
+    stack_try_pin_init!(let value: Rc<()> = chained(&rc, Some(true)).catch_unwind());
+    let err = value.err().unwrap();
+    assert!(matches!(err, InitPanic::Panic(_)));
+    assert_eq!(err.panic_message(), Some("chain panicked"));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn array_prefix_dropped() {
+    let rc = Rc::new(());
+    let init = pin_init_array_from_fn::<_, 8, _, i32>(|i| chained(&rc, (i == 5).then_some(true)));
+    let res = Box::<[Rc<()>; 8]>::new_uninit().write_pin_init(init.catch_unwind());
+    assert_eq!(
+        res.err().and_then(|e| e.panic_message().map(str::to_owned)),
+        Some("chain panicked".to_owned())
+    );
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn init() {
+    let init = init_array_from_fn::<_, 3, usize, i32>(|i| {
+        if i == 2 {
+            panic!("element {i}");
+        }
+        Ok(i)
+    });
+    let res = Box::<[usize; 3]>::new_uninit().write_init(init.catch_unwind());
+    assert_eq!(res.unwrap_err().panic_message(), Some("element 2"));
+}
@@ -0,0 +1,226 @@
+use core::{
+    marker::PhantomPinned,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+#[pin_data]
+struct Pinned {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn pinned(value: u32) -> impl PinInit<Pinned, Error> {
+    pin_init!(Pinned {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+fn fail<T>(code: u32) -> impl Init<T, Error> {
+    Err(Error(code))
+}
+
+#[pin_data]
+struct Foo {
+    a: u32,
+    #[pin]
+    b: Pinned,
+    c: u32,
+}
+
+fn foo(mode: u32) -> impl PinInit<Foo, Error> {
+    pin_init!(Foo {
+        a: 1,
+        if mode == 0 {
+            b <- pinned(10),
+            c: 20,
+        } else if mode == 1 {
+            c: 21,
+            b <- pinned(*a + 10),
+        } else {
+            b <- pinned(12),
+            c <- fail(mode),
+        }
+    }? Error)
+}
+
+#[test]
+fn if_else() {
+    stack_try_pin_init!(let first = foo(0));
+    let first = first.unwrap();
+    assert_eq!((first.a, first.b.value, first.c), (1, 10, 20));
+
+    stack_try_pin_init!(let second = foo(1));
+    let second = second.unwrap();
+    assert_eq!((second.a, second.b.value, second.c), (1, 11, 21));
+
+    stack_try_pin_init!(let third = foo(2));
+    assert_eq!(third.err(), Some(Error(2)));
+}
+
+enum Mode {
+    Fixed(u32),
+    Copy,
+    Fail,
+}
+
+fn bar(mode: Mode) -> impl PinInit<Foo, Error> {
+    pin_init!(Foo {
+        match mode {
+            Mode::Fixed(value) if value > 100 => {
+                a: 100,
+                b <- pinned(100),
+            }
+            Mode::Fixed(value) => {
+                a: value,
+                b <- pinned(value),
+            },
+            Mode::Copy => {
+                a: 5,
+                b <- pinned(*a),
+            }
+            Mode::Fail => {
+                b <- fail(7),
+                a: 0,
+            }
+        },
+        c: *a + b.value,
+    }? Error)
+}
+
+#[test]
+fn match_arms() {
+    stack_try_pin_init!(let fixed = bar(Mode::Fixed(3)));
+    let fixed = fixed.unwrap();
+    assert_eq!((fixed.a, fixed.b.value, fixed.c), (3, 3, 6));
+
+    stack_try_pin_init!(let clamped = bar(Mode::Fixed(300)));
+    assert_eq!(clamped.unwrap().c, 200);
+
+    stack_try_pin_init!(let copy = bar(Mode::Copy));
+    assert_eq!(copy.unwrap().c, 10);
+
+    stack_try_pin_init!(let fail = bar(Mode::Fail));
+    assert_eq!(fail.err(), Some(Error(7)));
+}
+
+#[test]
+fn for_loop() {
+    #[pin_data]
+    struct Table {
+        len: usize,
+        #[pin]
+        rows: [Pinned; 4],
+    }
+
+    let init = pin_init!(Table {
+        len: 4,
+        for i in 0..4 {
+            rows[i] <- pinned(i as u32 * 2)
+        }
+    }? Error);
+    stack_try_pin_init!(let table = init);
+    let table = table.unwrap();
+    assert_eq!(table.len, 4);
+    let values: Vec<_> = table.rows.iter().map(|row| row.value).collect();
+    assert_eq!(values, [0, 2, 4, 6]);
+}
+
+#[test]
+fn for_loop_full_range() {
+    struct Table<const N: usize> {
+        rows: [u32; N],
+    }
+
+    fn table<const N: usize>() -> impl Init<Table<N>, Error> {
+        init!(Table::<N> {
+            for i in .. {
+                rows[i] <- Ok::<_, Error>(i as u32),
+            }
+        }? Error)
+    }
+
+    stack_try_pin_init!(let table = table::<3>());
+    assert_eq!(table.unwrap().rows, [0, 1, 2]);
+}
+
+#[test]
+fn for_loop_generic_length() {
+    struct Table<const N: usize> {
+        rows: [u32; N],
+    }
+
+    fn table<const N: usize>() -> impl Init<Table<N>, Error> {
+        init!(Table::<N> {
+            for i in 0..N {
+                rows[i] <- Ok::<_, Error>(i as u32 + 1),
+            }
+        }? Error)
+    }
+
+    stack_try_pin_init!(let table = table::<2>());
+    assert_eq!(table.unwrap().rows, [1, 2]);
+}
+
+struct CountDrop<'a>(&'a AtomicUsize);
+
+impl Drop for CountDrop<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn drops_on_failure() {
+    struct Foo<'a> {
+        a: CountDrop<'a>,
+        b: CountDrop<'a>,
+        items: [CountDrop<'a>; 3],
+        c: CountDrop<'a>,
+    }
+
+    let drops = AtomicUsize::new(0);
+    let counter = &drops;
+    let init = init!(Foo {
+        if true {
+            a: CountDrop(counter),
+            b <- Ok::<_, Error>(CountDrop(counter)),
+        } else {
+            b: CountDrop(counter),
+            a: CountDrop(counter),
+        }
+        for i in 0..3 {
+            items[i] <- if i < 2 { Ok(CountDrop(counter)) } else { Err(Error(1)) },
+        }
+        c: CountDrop(counter),
+    }? Error);
+    stack_try_pin_init!(let foo: Foo<'_> = init);
+    assert_eq!(foo.err(), Some(Error(1)));
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn field_errors() {
+    struct Foo {
+        a: u32,
+        items: [u32; 2],
+    }
+
+    let init = init!(#[field_errors] Foo {
+        match 0 {
+            0 => { a <- fail(1) }
+            _ => { a: 0 }
+        }
+        for i in 0..2 {
+            items[i] <- Ok::<_, Error>(i as u32)
+        }
+    }? Error);
+    stack_try_pin_init!(let foo: Foo = init);
+    assert_eq!(foo.err().unwrap().field(), Some("a"));
+}
@@ -0,0 +1,261 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(all(feature = "debug-poison", feature = "std"))]
+
+use core::{convert::Infallible, mem::MaybeUninit, ptr::addr_of_mut};
+
+use pin_init::*;
+
+#[pin_data]
+struct Foo {
+    a: u32,
+    b: u64,
+    c: u8,
+}
+
+/// Only writes `a` and `c`.
+fn forgets_b() -> impl PinInit<Foo> {
+    // SAFETY: Deliberately wrong, `b` is not written.
+    unsafe {
+        pin_init_from_closure(|slot: *mut Foo| {
+            addr_of_mut!((*slot).a).write(1);
+            addr_of_mut!((*slot).c).write(3);
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn correct_init() {
+    let foo = Box::pin_init(pin_init!(Foo { a: 1, b: 2, c: 3 })).unwrap();
+    assert_eq!((foo.a, foo.b, foo.c), (1, 2, 3));
+    stack_pin_init!(let foo = pin_init!(Foo { a: 1, b: 2, c: 3 }));
+    assert_eq!(foo.b, 2);
+}
+
+#[pin_data]
+struct Outer {
+    #[pin]
+    foo: Foo,
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_box() {
+    let _ = Box::pin_init(forgets_b());
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_stack() {
+    stack_pin_init!(let _foo = forgets_b());
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_stack_try() {
+    stack_try_pin_init!(let _foo: Foo = forgets_b());
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_in_place_write() {
+    let _ = Box::<Foo>::new_uninit().write_pin_init(forgets_b());
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_pin_init() {
+    let _ = Box::pin_init(pin_init!(Outer { foo <- forgets_b() }));
+}
+
+struct Bar {
+    x: Foo,
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Foo` is still poisoned")]
+fn missing_field_init() {
+    // SAFETY: Deliberately wrong, `b` is not written.
+    let forgets_b = unsafe {
+        init_from_closure(|slot: *mut Foo| {
+            addr_of_mut!((*slot).a).write(1);
+            addr_of_mut!((*slot).c).write(3);
+            Ok::<_, Infallible>(())
+        })
+    };
+    stack_pin_init!(let _bar = init!(Bar { x <- forgets_b }));
+}
+
+#[derive(Zeroable)]
+#[pin_data]
+struct ZeroableFirst {
+    a: u32,
+    b: u32,
+}
+
+#[pin_data]
+#[derive(Zeroable)]
+struct PinDataFirst {
+    a: u32,
+    b: u32,
+}
+
+#[derive(Zeroable)]
+struct Tuple(u32, u16);
+
+#[pin_data]
+#[derive(Zeroable)]
+struct Fields {
+    zeroable_first: ZeroableFirst,
+    pin_data_first: PinDataFirst,
+    tuple: Tuple,
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::ZeroableFirst` is still poisoned")]
+fn zeroable_and_pin_data() {
+    // SAFETY: Deliberately wrong, `b` is not written.
+    let init = unsafe {
+        init_from_closure(|slot: *mut ZeroableFirst| {
+            addr_of_mut!((*slot).a).write(1);
+            Ok::<_, Infallible>(())
+        })
+    };
+    let _ = Box::pin_init(pin_init!(Fields {
+        zeroable_first <- init,
+        ..Zeroable::init_zeroed()
+    }));
+}
+
+#[test]
+#[should_panic(expected = "field `a` of `debug_poison::PinDataFirst` is still poisoned")]
+fn pin_data_and_zeroable() {
+    // SAFETY: Deliberately wrong, `a` is not written.
+    let init = unsafe {
+        init_from_closure(|slot: *mut PinDataFirst| {
+            addr_of_mut!((*slot).b).write(1);
+            Ok::<_, Infallible>(())
+        })
+    };
+    let _ = Box::pin_init(pin_init!(Fields {
+        pin_data_first <- init,
+        ..Zeroable::init_zeroed()
+    }));
+}
+
+#[test]
+#[should_panic(expected = "field `1` of `debug_poison::Tuple` is still poisoned")]
+fn tuple_struct() {
+    // SAFETY: Deliberately wrong, `1` is not written.
+    let init = unsafe {
+        init_from_closure(|slot: *mut Tuple| {
+            addr_of_mut!((*slot).0).write(1);
+            Ok::<_, Infallible>(())
+        })
+    };
+    let _ = Box::pin_init(pin_init!(Fields {
+        tuple <- init,
+        ..Zeroable::init_zeroed()
+    }));
+}
+
+#[pin_data]
+struct Buffer {
+    len: usize,
+    data: [MaybeUninit<u32>; 4],
+}
+
+#[test]
+fn maybe_uninit_is_not_checked() {
+    let buf = Box::pin_init(pin_init!(Buffer {
+        len: 0,
+        data: [MaybeUninit::uninit(); 4],
+    }))
+    .unwrap();
+    assert_eq!(buf.len, 0);
+}
+
+#[test]
+fn zeroed_is_not_poisoned() {
+    let foo = Box::init(init_zeroed::<ZeroableFirst>()).unwrap();
+    assert_eq!((foo.a, foo.b), (0, 0));
+}
+
+#[pin_data]
+struct Generic<T> {
+    value: T,
+    len: u32,
+}
+
+#[pin_data]
+struct GenericOuter {
+    #[pin]
+    generic: Generic<MaybeUninit<u32>>,
+}
+
+#[test]
+fn generic_maybe_uninit_is_not_checked() {
+    let init = pin_init!(Generic {
+        value: MaybeUninit::uninit(),
+        len: 0,
+    });
+    let outer = Box::pin_init(pin_init!(GenericOuter { generic <- init })).unwrap();
+    assert_eq!(outer.generic.len, 0);
+}
+
+#[pin_data]
+#[derive(Zeroable)]
+struct Padded {
+    a: u8,
+    b: u32,
+}
+
+#[pin_data]
+struct PaddedOuter {
+    #[pin]
+    padded: Padded,
+}
+
+#[test]
+fn padding_is_not_checked() {
+    let outer = Box::pin_init(pin_init!(PaddedOuter {
+        padded <- pin_init!(Padded { a: 1, b: 2 }),
+    }))
+    .unwrap();
+    assert_eq!((outer.padded.a, outer.padded.b), (1, 2));
+}
+
+#[test]
+#[should_panic(expected = "field `b` of `debug_poison::Padded` is still poisoned")]
+fn padded_missing_field() {
+    // SAFETY: Deliberately wrong, `b` is not written.
+    let init = unsafe {
+        pin_init_from_closure(|slot: *mut Padded| {
+            addr_of_mut!((*slot).a).write(1);
+            Ok::<_, Infallible>(())
+        })
+    };
+    let _ = Box::pin_init(pin_init!(PaddedOuter { padded <- init }));
+}
+
+#[cfg(feature = "dst")]
+#[pin_data]
+struct Packet {
+    id: u64,
+    data: [u8],
+}
+
+#[test]
+#[cfg(feature = "dst")]
+#[should_panic(expected = "field `id` of `debug_poison::Packet` is still poisoned")]
+fn missing_field_dst() {
+    // SAFETY: Deliberately wrong, `id` is not written.
+    let init = unsafe {
+        pin_init_from_closure(|slot: *mut Packet| {
+            let data = &raw mut (*slot).data;
+            data.cast::<u8>().write_bytes(0, data.len());
+            Ok::<_, Infallible>(())
+        })
+    };
+    let _ = Box::pin_init_dst(4, init);
+}
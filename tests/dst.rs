@@ -0,0 +1,111 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(feature = "dst")]
+
+use core::{
+    alloc::AllocError,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self(u32::MAX)
+    }
+}
+
+#[pin_data]
+struct Header {
+    len: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data]
+struct Packet {
+    #[pin]
+    header: Header,
+    checksum: u8,
+    data: [u8],
+}
+
+fn new_packet(payload: &[u8]) -> impl PinInit<Packet, Error> + '_ {
+    let len = payload.len();
+    pin_init!(#[dst] Packet {
+        header <- pin_init!(Header {
+            len,
+            _pin: PhantomPinned,
+        }? Error),
+        data <- init_slice_from_fn(len, |i| Ok::<_, Error>(payload[i])),
+        checksum: data.iter().fold(0, |acc, b| acc ^ b),
+    }? Error)
+}
+
+#[test]
+fn trailing_slice() {
+    let packet: Pin<Box<Packet>> = Box::try_pin_init_dst(3, new_packet(&[1, 2, 4])).unwrap();
+    assert_eq!(packet.header.len, 3);
+    assert_eq!(packet.checksum, 7);
+    assert_eq!(&packet.data, &[1, 2, 4]);
+
+    let empty: Pin<Box<Packet>> = Box::try_pin_init_dst(0, new_packet(&[])).unwrap();
+    assert_eq!((empty.header.len, empty.data.len()), (0, 0));
+}
+
+#[test]
+fn slice() {
+    let slice: Box<[u64]> = Box::init_dst(5, init_slice_from_fn(5, |i| i as u64)).unwrap();
+    assert_eq!(*slice, [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn too_large() {
+    let res: Result<Box<[u64]>, Error> = Box::try_init_dst(
+        usize::MAX / 4,
+        init_slice_from_fn(usize::MAX / 4, |_| Ok(0)),
+    );
+    assert_eq!(res.err(), Some(Error(u32::MAX)));
+}
+
+#[test]
+#[should_panic(expected = "has to be used on a slice of the same length")]
+fn length_mismatch() {
+    let _ = Box::<[u8]>::init_dst(4, init_slice_from_fn(3, |_| 0));
+}
+
+struct CountDrop<'a>(&'a AtomicUsize);
+
+impl Drop for CountDrop<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn drops_on_failure() {
+    struct List<'a> {
+        head: CountDrop<'a>,
+        items: [CountDrop<'a>],
+    }
+
+    let drops = AtomicUsize::new(0);
+    let counter = &drops;
+    let init = init!(#[dst] List {
+        head: CountDrop(counter),
+        items <- init_slice_from_fn(4, |i| {
+            if i < 2 {
+                Ok(CountDrop(counter))
+            } else {
+                Err(Error(i as u32))
+            }
+        }),
+    }? Error);
+    let res: Result<Box<List<'_>>, Error> = Box::try_init_dst(4, init);
+    assert_eq!(res.err(), Some(Error(2)));
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
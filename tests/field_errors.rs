@@ -0,0 +1,208 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+fn fail<T>(code: u32) -> impl Init<T, Error> {
+    Err(Error(code))
+}
+
+#[pin_data]
+struct Irq {
+    number: u32,
+}
+
+#[pin_data]
+struct Queue {
+    len: usize,
+    #[pin]
+    irq: Irq,
+}
+
+#[pin_data]
+struct Device {
+    id: u64,
+    #[pin]
+    queue: Queue,
+}
+
+fn irq(number: u32) -> impl PinInit<Irq, InitError<Error>> {
+    pin_init!(#[field_errors] Irq {
+        number <- if number == 0 { Err(Error(number)) } else { Ok(number) },
+    }? Error)
+}
+
+fn queue(irq_number: u32) -> impl PinInit<Queue, InitError<Error>> {
+    pin_init!(#[field_errors] Queue {
+        len: 16,
+        irq <- irq(irq_number),
+    }? Error)
+}
+
+#[test]
+fn innermost_field() {
+    stack_try_pin_init!(let irq = irq(0));
+    let err = irq.err().unwrap();
+    assert_eq!(err.source, Error(0));
+    assert_eq!(err.field(), Some("number"));
+    assert_eq!(err.path().collect::<Vec<_>>(), ["Irq", "number"]);
+    assert_eq!(err.to_string(), "failed to initialize `Irq.number`");
+}
+
+#[test]
+fn nested_path() {
+    let device = pin_init!(#[field_errors] Device {
+        id: 42,
+        queue <- queue(0),
+    }? Error);
+    stack_try_pin_init!(let device = device);
+    let err = device.err().unwrap();
+    assert_eq!(err.source, Error(0));
+    assert_eq!(err.field(), Some("number"));
+    assert_eq!(
+        err.path().collect::<Vec<_>>(),
+        ["Device", "queue", "irq", "number"]
+    );
+    assert_eq!(
+        format!("{err:?}"),
+        "InitError { path: Device.queue.irq.number, source: Error(0) }"
+    );
+}
+
+#[test]
+fn success() {
+    stack_try_pin_init!(let queue = queue(7));
+    assert_eq!(queue.unwrap().irq.number, 7);
+}
+
+#[test]
+fn code_block_error() {
+    struct Foo {
+        a: u32,
+    }
+
+    let init = init!(#[field_errors] Foo {
+        _: {
+            Err(Error(1))?;
+        },
+        a: 0,
+    }? Error);
+    stack_try_pin_init!(let foo: Foo = init);
+    let err = foo.err().unwrap();
+    assert_eq!(err.source, Error(1));
+    assert_eq!(err.field(), None);
+    assert_eq!(err.path().count(), 0);
+}
+
+#[test]
+fn drops_initialized_fields() {
+    struct CountDrop<'a>(&'a AtomicUsize);
+
+    impl Drop for CountDrop<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Foo<'a> {
+        a: CountDrop<'a>,
+        b: CountDrop<'a>,
+    }
+
+    let drops = AtomicUsize::new(0);
+    let counter = &drops;
+    let init = init!(#[field_errors] Foo {
+        a: CountDrop(counter),
+        b <- fail(3),
+    }? Error);
+    stack_try_pin_init!(let foo: Foo<'_> = init);
+    assert_eq!(foo.err().unwrap().field(), Some("b"));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn truncated_path() {
+    struct Leaf {
+        value: u32,
+    }
+
+    struct Node<T> {
+        inner: T,
+    }
+
+    fn node<T>(inner: impl Init<T, InitError<Error>>) -> impl Init<Node<T>, InitError<Error>> {
+        init!(#[field_errors] Node { inner <- inner }? Error)
+    }
+
+    let leaf = init!(#[field_errors] Leaf { value <- fail(5) }? Error);
+    let init = node(node(node(node(node(node(leaf))))));
+    stack_try_pin_init!(let tree = init);
+    let err = tree.err().unwrap();
+    assert_eq!(err.path().count(), InitError::<Error>::MAX_DEPTH);
+    assert_eq!(err.field(), Some("value"));
+    assert!(err
+        .to_string()
+        .ends_with(">.[..].inner.inner.inner.inner.value`"));
+}
+
+#[test]
+fn converted_error() {
+    #[derive(Debug, PartialEq)]
+    struct LowLevel(u32);
+
+    impl From<LowLevel> for Error {
+        fn from(err: LowLevel) -> Self {
+            Error(err.0 + 100)
+        }
+    }
+
+    struct Foo {
+        a: u32,
+        b: u32,
+    }
+
+    let init = init!(#[field_errors] Foo {
+        a: 0,
+        b <- Err::<u32, _>(LowLevel(2)),
+    }? Error);
+    stack_try_pin_init!(let foo: Foo = init);
+    let err = foo.err().unwrap();
+    assert_eq!(err.source, Error(102));
+    assert_eq!(err.to_string(), "failed to initialize `Foo.b`");
+}
+
+#[test]
+fn array_index() {
+    #[pin_data]
+    struct Device {
+        id: u64,
+        #[pin]
+        queues: [Queue; 4],
+    }
+
+    let device = pin_init!(#[field_errors] Device {
+        id: 0,
+        for i in 0..4 {
+            queues[i] <- queue(if i == 3 { 0 } else { 1 })
+        }
+    }? Error);
+    stack_try_pin_init!(let device = device);
+    let err = device.err().unwrap();
+    assert_eq!(err.field(), Some("number"));
+    assert_eq!(
+        err.path().collect::<Vec<_>>(),
+        [
+            PathSegment::Name("Device"),
+            PathSegment::Name("queues"),
+            PathSegment::Index(3),
+            PathSegment::Name("irq"),
+            PathSegment::Name("number"),
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        "failed to initialize `Device.queues[3].irq.number`"
+    );
+}
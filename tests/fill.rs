@@ -0,0 +1,102 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(any(feature = "std", feature = "alloc"))]
+
+use core::convert::Infallible;
+use std::thread;
+
+use pin_init::*;
+
+const BIG: usize = 16 * 1024 * 1024;
+
+/// Runs `f` on a thread whose stack is much smaller than the arrays that are initialized, so any
+/// copy of them on the stack overflows it.
+fn with_small_stack(f: impl FnOnce() + Send + 'static) {
+    thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn repeat() {
+    fn check<const N: usize>() {
+        let array: Box<[u16; N]> = Box::init(init_array_repeat(0xabcd)).unwrap();
+        assert!(array.iter().all(|x| *x == 0xabcd));
+    }
+    check::<0>();
+    check::<1>();
+    check::<3>();
+    check::<1000>();
+
+    let units: Box<[(); 17]> = Box::init(init_array_repeat(())).unwrap();
+    assert_eq!(units.len(), 17);
+}
+
+#[test]
+fn copy_from() {
+    let src = [1u64, 2, 3, 5, 8];
+    let array: Box<[u64; 5]> = Box::init(init_array_copy_from(&src)).unwrap();
+    assert_eq!(*array, src);
+}
+
+#[test]
+fn in_init_macro() {
+    struct Buffer {
+        len: usize,
+        data: [u8; 64],
+        table: [u32; 4],
+    }
+
+    let table = [4, 3, 2, 1];
+    let buf = Box::init(init!(Buffer {
+        len: 64,
+        data <- init_array_repeat::<_, 64, Infallible>(0x55),
+        table <- init_array_copy_from::<_, 4, Infallible>(&table),
+    }))
+    .unwrap();
+    assert_eq!(buf.len, 64);
+    assert_eq!(buf.data, [0x55; 64]);
+    assert_eq!(buf.table, table);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn big_repeat() {
+    with_small_stack(|| {
+        let array: Box<[u8; BIG]> = Box::init(init_array_repeat(0xa5)).unwrap();
+        assert!(array.iter().all(|x| *x == 0xa5));
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn big_copy_from() {
+    with_small_stack(|| {
+        let mut src: Box<[u32; BIG / 4]> = Box::init(init_array_repeat(0)).unwrap();
+        for (i, x) in src.iter_mut().enumerate() {
+            *x = i as u32;
+        }
+        let copy: Box<[u32; BIG / 4]> = Box::init(init_array_copy_from(&src)).unwrap();
+        assert!(copy.iter().enumerate().all(|(i, x)| *x == i as u32));
+    });
+}
+
+#[test]
+#[cfg(feature = "dst")]
+#[cfg_attr(miri, ignore)]
+fn big_slice_fill() {
+    with_small_stack(|| {
+        let slice: Box<[u64]> = Box::init_dst(BIG / 8, init_slice_fill(u64::MAX, BIG / 8)).unwrap();
+        assert_eq!(slice.len(), BIG / 8);
+        assert!(slice.iter().all(|x| *x == u64::MAX));
+    });
+}
+
+#[test]
+#[cfg(feature = "dst")]
+#[should_panic(expected = "has to be used on a slice of the same length")]
+fn slice_fill_length_mismatch() {
+    let _ = Box::<[u8]>::init_dst(4, init_slice_fill(0, 5));
+}
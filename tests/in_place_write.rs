@@ -0,0 +1,94 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(any(feature = "std", feature = "alloc"))]
+
+use core::marker::PhantomPinned;
+use std::{rc::Rc, sync::Arc};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(usize);
+
+#[pin_data]
+struct Pinned {
+    value: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn pinned(value: usize) -> impl PinInit<Pinned, Error> {
+    pin_init!(Pinned {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+#[test]
+fn arc() {
+    let arc = Unique::new(Arc::<Pinned>::new_uninit()).ok().unwrap();
+    let arc = arc.write_pin_init(pinned(1)).unwrap();
+    assert_eq!(arc.value, 1);
+
+    let arc = Unique::new(Arc::<usize>::new_uninit()).ok().unwrap();
+    let arc = arc.write_init(Ok::<_, Error>(2)).unwrap();
+    assert_eq!(*arc, 2);
+
+    let arc = Unique::new(Arc::<usize>::new_uninit()).ok().unwrap();
+    assert_eq!(arc.write_init(Err(Error(3))).err(), Some(Error(3)));
+}
+
+#[test]
+fn rc() {
+    let rc = Unique::new(Rc::<Pinned>::new_uninit()).ok().unwrap();
+    let rc = rc.write_pin_init(pinned(1)).unwrap();
+    assert_eq!(rc.value, 1);
+
+    let rc = Unique::new(Rc::<usize>::new_uninit()).ok().unwrap();
+    assert_eq!(rc.write_init(Err(Error(3))).err(), Some(Error(3)));
+}
+
+#[test]
+fn shared() {
+    let arc = Arc::<Pinned>::new_uninit();
+    let weak = Arc::downgrade(&arc);
+    let arc = Unique::new(arc).err().unwrap();
+    drop(weak);
+    let arc = Unique::new(arc).ok().unwrap();
+    let arc = arc.write_pin_init(pinned(1)).unwrap();
+    assert_eq!(arc.value, 1);
+
+    let rc = Rc::<usize>::new_uninit();
+    let other = rc.clone();
+    let rc = Unique::new(rc).err().unwrap();
+    drop(other);
+    let rc = Unique::new(rc).ok().unwrap().into_inner();
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn boxed_slice() {
+    let slice = Box::<[Pinned]>::new_uninit_slice(8);
+    let slice = slice.write_pin_init_slice(pinned).unwrap();
+    assert!(slice.iter().enumerate().all(|(i, p)| p.value == i));
+
+    let slice = Box::<[usize]>::new_uninit_slice(0);
+    let slice = slice
+        .write_init_slice(|_| -> Result<usize, Error> { unreachable!() })
+        .unwrap();
+    assert!(slice.is_empty());
+}
+
+#[test]
+fn boxed_slice_failure_drops_prefix() {
+    let rc = Rc::new(());
+    let slice = Box::<[Rc<()>]>::new_uninit_slice(8);
+    let res = slice.write_init_slice(|i| {
+        if i == 5 {
+            Err(Error(i))
+        } else {
+            Ok(rc.clone())
+        }
+    });
+    assert_eq!(res.err().map(|e| e.0), Some(5));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
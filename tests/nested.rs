@@ -0,0 +1,259 @@
+use core::{
+    cell::Cell,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+#[pin_data]
+struct Pinned {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn pinned(value: u32) -> impl PinInit<Pinned, Error> {
+    pin_init!(Pinned {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+#[pin_data]
+struct Inner {
+    a: u32,
+    #[pin]
+    b: Pinned,
+}
+
+#[pin_data]
+struct Outer {
+    #[pin]
+    inner: Inner,
+    c: u32,
+}
+
+#[test]
+fn pinned_nested() {
+    let init = pin_init!(Outer {
+        inner: Inner {
+            a: 1,
+            b <- pinned(2),
+        },
+        c: 3,
+    }? Error);
+    stack_try_pin_init!(let outer = init);
+    let outer = outer.unwrap();
+    assert_eq!(outer.inner.a, 1);
+    assert_eq!(outer.inner.b.value, 2);
+    assert_eq!(outer.c, 3);
+}
+
+#[test]
+fn let_bindings() {
+    let init = pin_init!(Outer {
+        inner: Inner {
+            a: 1,
+            b <- pinned(*a + 1),
+        },
+        c: inner.as_ref().b.value + 1,
+    }? Error);
+    stack_try_pin_init!(let outer = init);
+    let outer = outer.unwrap();
+    assert_eq!(outer.inner.b.value, 2);
+    assert_eq!(outer.c, 3);
+}
+
+#[test]
+fn unpinned_nested() {
+    struct Foo {
+        inner: Inner,
+    }
+
+    // `inner` is not structurally pinned, so its fields only accept `Init`.
+    let init = init!(Foo {
+        inner: Inner {
+            a: 1,
+            b <- init!(Pinned { value: 2, _pin: PhantomPinned }),
+        },
+    });
+    stack_pin_init!(let foo: Foo = init);
+    let foo: Pin<&mut Foo> = foo;
+    assert_eq!(foo.inner.b.value, 2);
+}
+
+#[test]
+fn struct_value() {
+    struct Point {
+        x: u32,
+        y: u32,
+    }
+
+    struct Line {
+        start: Point,
+        end: Point,
+    }
+
+    let x = 1;
+    let init = init!(Line {
+        start: Point { x, y: 2 },
+        end: Point {
+            _: {},
+            x: 3,
+            y: 4,
+        },
+    });
+    stack_pin_init!(let line: Line = init);
+    assert_eq!((line.start.x, line.start.y), (1, 2));
+    assert_eq!((line.end.x, line.end.y), (3, 4));
+}
+
+#[test]
+fn zeroing() {
+    #[derive(Zeroable)]
+    struct Inner {
+        a: u32,
+        b: u32,
+    }
+
+    struct Foo {
+        inner: Inner,
+    }
+
+    let init = init!(Foo {
+        inner: Inner {
+            b <- Ok::<_, Error>(2),
+            ..Zeroable::init_zeroed()
+        },
+    }? Error);
+    stack_try_pin_init!(let foo: Foo = init);
+    let foo = foo.unwrap();
+    assert_eq!((foo.inner.a, foo.inner.b), (0, 2));
+}
+
+struct CountDrop<'a>(&'a AtomicUsize);
+
+impl Drop for CountDrop<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn fail<T>(code: u32) -> impl Init<T, Error> {
+    Err(Error(code))
+}
+
+#[test]
+fn inner_failure_drops_fields() {
+    struct Inner<'a> {
+        a: CountDrop<'a>,
+        b: CountDrop<'a>,
+    }
+
+    struct Foo<'a> {
+        first: CountDrop<'a>,
+        inner: Inner<'a>,
+    }
+
+    let drops = AtomicUsize::new(0);
+    let counter = &drops;
+    let init = init!(Foo {
+        first: CountDrop(counter),
+        inner: Inner {
+            a: CountDrop(counter),
+            b <- fail(1),
+        },
+    }? Error);
+    stack_try_pin_init!(let foo: Foo<'_> = init);
+    assert_eq!(foo.err(), Some(Error(1)));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn outer_failure_drops_nested() {
+    struct Inner<'a> {
+        a: CountDrop<'a>,
+        b: CountDrop<'a>,
+    }
+
+    struct Foo<'a> {
+        inner: Inner<'a>,
+        last: CountDrop<'a>,
+    }
+
+    let drops = AtomicUsize::new(0);
+    let counter = &drops;
+    let init = init!(Foo {
+        inner: Inner {
+            a: CountDrop(counter),
+            b <- Ok::<_, Error>(CountDrop(counter)),
+        },
+        last <- fail(2),
+    }? Error);
+    stack_try_pin_init!(let foo: Foo<'_> = init);
+    assert_eq!(foo.err(), Some(Error(2)));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn field_errors() {
+    struct Leaf {
+        value: u32,
+    }
+
+    struct Branch {
+        leaf: Leaf,
+    }
+
+    struct Root {
+        branch: Branch,
+    }
+
+    let init = init!(#[field_errors] Root {
+        branch: Branch {
+            leaf: Leaf {
+                value <- fail(3),
+            },
+        },
+    }? Error);
+    stack_try_pin_init!(let root: Root = init);
+    let err = root.err().unwrap();
+    assert_eq!(err.source, Error(3));
+    assert_eq!(
+        err.path().collect::<Vec<_>>(),
+        ["Root", "branch", "leaf", "value"]
+    );
+}
+
+#[test]
+fn same_field_names() {
+    struct A {
+        a: Cell<u32>,
+    }
+
+    struct B {
+        a: A,
+        b: u32,
+    }
+
+    struct C {
+        a: B,
+    }
+
+    let init = init!(C {
+        a: B {
+            a: A {
+                a <- Ok::<_, Error>(Cell::new(1)),
+            },
+            b <- Ok::<_, Error>(a.a.get() + 1),
+        },
+    }? Error);
+    stack_try_pin_init!(let c: C = init);
+    let c = c.unwrap();
+    assert_eq!((c.a.a.a.get(), c.a.b), (1, 2));
+}
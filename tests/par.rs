@@ -0,0 +1,144 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(feature = "std")]
+
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    thread,
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(usize);
+
+#[cfg(feature = "alloc")]
+impl From<core::alloc::AllocError> for Error {
+    fn from(_: core::alloc::AllocError) -> Self {
+        Self(usize::MAX)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl From<core::convert::Infallible> for Error {
+    fn from(e: core::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
+struct Counted<'a> {
+    index: usize,
+    drops: &'a AtomicUsize,
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+const LEN: usize = 10_000;
+
+fn counted<'a>(
+    created: &'a AtomicUsize,
+    drops: &'a AtomicUsize,
+    fail: impl Fn(usize) -> bool + Sync,
+) -> impl Init<[Counted<'a>; LEN], Error> {
+    par_init_array_from_fn(move |index| {
+        if fail(index) {
+            Err(Error(index))
+        } else {
+            created.fetch_add(1, Ordering::Relaxed);
+            Ok(Counted { index, drops })
+        }
+    })
+}
+
+#[test]
+fn all_elements() {
+    let ids: Box<[thread::ThreadId; LEN]> =
+        Box::init(par_init_array_from_fn(|_| thread::current().id())).unwrap();
+    assert!(ids.iter().all(|id| *id != thread::current().id()));
+
+    let created = AtomicUsize::new(0);
+    let drops = AtomicUsize::new(0);
+    let array: Result<Box<_>, Error> = Box::try_init(counted(&created, &drops, |_| false));
+    let array = array.unwrap();
+    assert!(array.iter().enumerate().all(|(i, c)| c.index == i));
+    drop(array);
+    assert_eq!(created.load(Ordering::Relaxed), LEN);
+    assert_eq!(drops.load(Ordering::Relaxed), LEN);
+}
+
+#[test]
+fn failure_drops_initialized() {
+    let created = AtomicUsize::new(0);
+    let drops = AtomicUsize::new(0);
+    let array: Result<Box<_>, Error> =
+        Box::try_init(counted(&created, &drops, |i| i == 7 || i == LEN - 3));
+    assert_eq!(array.err(), Some(Error(7)));
+    assert_eq!(
+        created.load(Ordering::Relaxed),
+        drops.load(Ordering::Relaxed)
+    );
+}
+
+#[test]
+fn failure_stops_other_chunks() {
+    let created = AtomicUsize::new(0);
+    let drops = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let array: Result<Box<_>, Error> = Box::try_init(counted(&created, &drops, |i| {
+        if i == 0 {
+            failed.store(true, Ordering::Relaxed);
+            return true;
+        }
+        while !failed.load(Ordering::Relaxed) {
+            thread::yield_now();
+        }
+        false
+    }));
+    assert_eq!(array.err(), Some(Error(0)));
+    assert!(created.load(Ordering::Relaxed) < LEN - 1);
+    assert_eq!(
+        created.load(Ordering::Relaxed),
+        drops.load(Ordering::Relaxed)
+    );
+}
+
+#[test]
+fn panic_drops_initialized() {
+    let created = AtomicUsize::new(0);
+    let drops = AtomicUsize::new(0);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let _: Result<Box<_>, Error> = Box::try_init(counted(&created, &drops, |i| {
+            assert_ne!(i, LEN / 2, "element panicked");
+            false
+        }));
+    }));
+    assert!(res.is_err());
+    assert_eq!(
+        created.load(Ordering::Relaxed),
+        drops.load(Ordering::Relaxed)
+    );
+}
+
+#[test]
+fn pinned() {
+    #[allow(unused_attributes)]
+    #[path = "../examples/mutex.rs"]
+    mod mutex;
+    use mutex::*;
+
+    stack_pin_init!(let array: [CMutex<usize>; 3] = par_pin_init_array_from_fn(|i| CMutex::new(i * 10)));
+    assert_eq!(*array[2].lock(), 20);
+}
+
+#[test]
+fn empty_slice() {
+    let mut slot = core::mem::MaybeUninit::<[u8; 0]>::uninit();
+    let slot: *mut [u8] = slot.as_mut_ptr();
+    // SAFETY: `slot` is valid for writes of zero elements.
+    let res = unsafe { par_init_slice_from_fn(0, |_| Ok::<_, Error>(0u8)).__init(slot) };
+    assert_eq!(res, Ok(()));
+}
@@ -0,0 +1,131 @@
+use core::{convert::Infallible, marker::PhantomPinned};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+#[pin_data]
+struct Inner {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Inner {
+    fn new(value: u32) -> impl PinInit<Self, Error> {
+        pin_init!(Self {
+            value,
+            _pin: PhantomPinned,
+        }? Error)
+    }
+}
+
+#[pin_data(builder)]
+struct Foo {
+    #[pin]
+    inner: Inner,
+    count: u64,
+    #[new(value)]
+    name: &'static str,
+    #[new(default)]
+    hits: usize,
+    #[new(default = 42)]
+    answer: u8,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[test]
+fn required_fields() {
+    let init = Foo::pin_init_builder()
+        .name("foo")
+        .count(Ok(2))
+        .inner(Inner::new(1))
+        .build();
+    stack_try_pin_init!(let foo: Foo = init);
+    let foo = foo.unwrap();
+    assert_eq!(foo.inner.value, 1);
+    assert_eq!(foo.count, 2);
+    assert_eq!(foo.name, "foo");
+    assert_eq!(foo.hits, 0);
+    assert_eq!(foo.answer, 42);
+}
+
+#[test]
+fn overridden_defaults() {
+    let init = Foo::pin_init_builder()
+        .inner(Inner::new(1))
+        .count(Ok(2))
+        .name("foo")
+        .hits(Ok(3))
+        .answer(Ok(4))
+        .build();
+    stack_try_pin_init!(let foo: Foo = init);
+    let foo = foo.unwrap();
+    assert_eq!(foo.hits, 3);
+    assert_eq!(foo.answer, 4);
+}
+
+#[test]
+fn error() {
+    let init = Foo::pin_init_builder()
+        .inner(Inner::new(1))
+        .count(Ok(2))
+        .name("foo")
+        .answer(Err(Error))
+        .build();
+    stack_try_pin_init!(let foo: Foo = init);
+    assert_eq!(foo.err(), Some(Error));
+}
+
+#[pin_data(builder)]
+struct Generic<'a, T: Clone, const N: usize>
+where
+    T: Copy,
+{
+    #[pin]
+    array: [T; N],
+    #[new(value)]
+    slice: &'a [T],
+}
+
+#[test]
+fn generics() {
+    let data = [1, 2];
+    let init = Generic::<u8, 4>::pin_init_builder::<Infallible>()
+        .slice(&data)
+        .array(init_array_repeat(7))
+        .build();
+    stack_pin_init!(let generic = init);
+    assert_eq!(generic.array, [7; 4]);
+    assert_eq!(generic.slice, [1, 2]);
+}
+
+// Without a trailing comma after the last predicate.
+#[rustfmt::skip]
+#[pin_data(builder)]
+struct WhereClause<T> where T: Clone {
+    a: T,
+}
+
+#[test]
+fn where_clause_without_trailing_comma() {
+    let init = WhereClause::pin_init_builder::<Infallible>().a(5u8).build();
+    stack_pin_init!(let value = init);
+    assert_eq!(value.a, 5);
+}
+
+#[pin_data(builder)]
+struct WithDefault<T = u32, const N: usize = 2> {
+    a: [T; N],
+}
+
+#[test]
+fn generic_defaults() {
+    let init = WithDefault::<u32>::pin_init_builder::<Infallible>()
+        .a(init_array_repeat(3))
+        .build();
+    stack_pin_init!(let value = init);
+    assert_eq!(value.a, [3, 3]);
+}
@@ -0,0 +1,76 @@
+use core::{convert::Infallible, marker::PhantomPinned};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+#[pin_data]
+struct Inner {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Inner {
+    fn new(value: u32) -> impl PinInit<Self, Error> {
+        pin_init!(Self {
+            value,
+            _pin: PhantomPinned,
+        }? Error)
+    }
+}
+
+#[pin_data(new)]
+struct Foo {
+    #[pin]
+    inner: Inner,
+    count: u64,
+    #[new(value)]
+    name: &'static str,
+    #[new(default)]
+    hits: usize,
+    #[new(default = 42)]
+    answer: u8,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[test]
+fn generated_new() {
+    stack_try_pin_init!(let foo: Foo = Foo::new(Inner::new(1), Ok(2), "foo"));
+    let foo = foo.unwrap();
+    assert_eq!(foo.inner.value, 1);
+    assert_eq!(foo.count, 2);
+    assert_eq!(foo.name, "foo");
+    assert_eq!(foo.hits, 0);
+    assert_eq!(foo.answer, 42);
+}
+
+#[test]
+fn generated_new_error() {
+    stack_try_pin_init!(let foo: Foo = Foo::new(Inner::new(1), Err(Error), "foo"));
+    assert_eq!(foo.err(), Some(Error));
+}
+
+#[pin_data(PinnedDrop, new)]
+struct Generic<T: Clone, const N: usize> {
+    #[pin]
+    array: [T; N],
+    #[cfg(not(any()))]
+    len: usize,
+    #[cfg(any())]
+    missing: usize,
+}
+
+#[pinned_drop]
+impl<T: Clone, const N: usize> PinnedDrop for Generic<T, N> {
+    fn drop(self: core::pin::Pin<&mut Self>) {}
+}
+
+#[test]
+fn generics_and_cfgs() {
+    stack_pin_init!(let generic = Generic::<u8, 4>::new::<Infallible>(init_array_repeat(7), 4));
+    assert_eq!(generic.array, [7; 4]);
+    assert_eq!(generic.len, 4);
+}
@@ -0,0 +1,126 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::marker::PhantomPinned;
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+/// Stands in for types of other crates and bindings generated by bindgen.
+mod remote {
+    use core::marker::PhantomPinned;
+
+    pub struct Counter {
+        pub value: u32,
+        pub _pin: PhantomPinned,
+    }
+
+    pub struct Device {
+        pub counter: Counter,
+        pub irq: u32,
+        pub name: &'static str,
+    }
+
+    #[repr(C)]
+    pub struct info {
+        pub id: u16,
+        pub flags: u64,
+    }
+
+    pub struct Wrapper<T> {
+        pub value: T,
+        pub _pin: PhantomPinned,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Counter)]
+struct Counter {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[pin_data(unsafe remote = remote::Device)]
+struct Device {
+    #[pin]
+    counter: remote::Counter,
+    irq: u32,
+    name: &'static str,
+}
+
+fn counter(value: u32) -> impl PinInit<remote::Counter, Error> {
+    pin_init!(Counter {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+#[test]
+fn remote_struct() {
+    stack_try_pin_init!(let device: remote::Device = pin_init!(Device {
+        counter <- counter(1),
+        irq: 5,
+        name: "uart",
+    }? Error));
+    let device = device.unwrap();
+    assert_eq!(device.counter.value, 1);
+    assert_eq!(device.irq, 5);
+    assert_eq!(device.name, "uart");
+}
+
+#[test]
+fn nested() {
+    stack_pin_init!(let device: remote::Device = pin_init!(Device {
+        counter: Counter { value: 2, _pin: PhantomPinned, _: {} },
+        irq: 5,
+        name: "uart",
+    }));
+    assert_eq!(device.counter.value, 2);
+}
+
+#[test]
+fn error() {
+    stack_try_pin_init!(let device: remote::Device = pin_init!(Device {
+        irq: 5,
+        counter <- pin_init!(Counter {
+            value <- Err(Error),
+            _pin: PhantomPinned,
+        }? Error),
+        name: "uart",
+    }? Error));
+    assert_eq!(device.err(), Some(Error));
+}
+
+#[pin_data(unsafe remote = remote::info)]
+#[repr(C)]
+struct Info {
+    id: u16,
+    flags: u64,
+}
+
+#[test]
+fn repr_c() {
+    stack_pin_init!(let info: remote::info = pin_init!(Info { id: 1, flags: 2 }));
+    assert_eq!((info.id, info.flags), (1, 2));
+}
+
+#[pin_data(unsafe remote = remote::Wrapper<T>)]
+struct Wrapper<T> {
+    value: T,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn generics() {
+    use core::convert::Infallible;
+
+    let wrapper = Box::pin_init(pin_init!(Wrapper::<u64> {
+        value: 7,
+        _pin: PhantomPinned,
+    }? Infallible))
+    .unwrap();
+    assert_eq!(wrapper.value, 7);
+}
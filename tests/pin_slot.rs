@@ -0,0 +1,81 @@
+use core::pin::pin;
+use std::rc::Rc;
+
+use pin_init::*;
+
+#[test]
+fn reuse() {
+    let first = Rc::new(0);
+    let mut slot = pin!(PinSlot::<Rc<i32>>::new());
+    assert!(!slot.is_init());
+    assert!(slot.as_mut().value_mut().is_none());
+    let value = slot
+        .as_mut()
+        .try_pin_init(Ok::<_, ()>(first.clone()))
+        .unwrap();
+    assert_eq!(**value, 0);
+    for id in 1..4 {
+        let value = slot
+            .as_mut()
+            .try_pin_init(Ok::<_, ()>(Rc::new(id)))
+            .unwrap();
+        assert_eq!(**value, id);
+        assert!(slot.is_init());
+    }
+    assert_eq!(Rc::strong_count(&first), 1);
+    let last = Rc::clone(&slot.as_ref().value().unwrap());
+    slot.as_mut().take_drop();
+    assert!(!slot.is_init());
+    assert_eq!(Rc::strong_count(&last), 1);
+    slot.as_mut().take_drop();
+    assert!(!slot.is_init());
+}
+
+#[test]
+fn error_leaves_slot_empty() {
+    let first = Rc::new(1);
+    let mut slot = pin!(PinSlot::<Rc<i32>>::new());
+    slot.as_mut()
+        .try_pin_init(Ok::<_, i32>(first.clone()))
+        .unwrap();
+    let res = slot.as_mut().try_pin_init(Err(2));
+    assert_eq!(res.err(), Some(2));
+    assert!(!slot.is_init());
+    assert!(slot.as_ref().value().is_none());
+    assert_eq!(Rc::strong_count(&first), 1);
+}
+
+#[test]
+fn drops_value_with_slot() {
+    let value = Rc::new(1);
+    {
+        let mut slot = pin!(PinSlot::<Rc<i32>>::new());
+        slot.as_mut()
+            .try_pin_init(Ok::<_, ()>(value.clone()))
+            .unwrap();
+    }
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[pin_data]
+#[derive(Zeroable)]
+struct Server {
+    #[pin]
+    request: PinSlot<Rc<usize>>,
+    handled: usize,
+}
+
+#[test]
+fn pinned_field() {
+    let first = Rc::new(0);
+    stack_pin_init!(let server = init_zeroed::<Server>());
+    for request in [first.clone(), Rc::new(1)] {
+        let this = server.as_mut().project();
+        this.request.try_pin_init(Ok::<_, ()>(request)).unwrap();
+        *this.handled += 1;
+    }
+    let this = server.as_mut().project();
+    assert_eq!(this.request.value_mut().map(|r| **r), Some(1));
+    assert_eq!(server.handled, 2);
+    assert_eq!(Rc::strong_count(&first), 1);
+}
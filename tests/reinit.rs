@@ -0,0 +1,50 @@
+use std::rc::Rc;
+
+use pin_init::*;
+
+#[test]
+fn replaces_value_in_place() {
+    let old = Rc::new(1usize);
+    stack_pin_init!(let value = old.clone());
+    let addr = &*value as *const Rc<usize>;
+    reinit(value.as_mut(), Rc::new(2));
+    assert_eq!(**value, 2);
+    assert_eq!(&*value as *const Rc<usize>, addr);
+    assert_eq!(Rc::strong_count(&old), 1);
+}
+
+#[test]
+fn fallback_on_error() {
+    let old = Rc::new(1);
+    stack_pin_init!(let value = old.clone());
+    let res = try_reinit(value.as_mut(), Err(3), Rc::new(4));
+    assert_eq!(res, Err(3));
+    assert_eq!(**value, 4);
+    assert_eq!(Rc::strong_count(&old), 1);
+
+    let res = try_reinit(value.as_mut(), Ok::<_, i32>(Rc::new(5)), Rc::new(6));
+    assert_eq!(res, Ok(()));
+    assert_eq!(**value, 5);
+}
+
+#[pin_data]
+struct Outer {
+    #[pin]
+    inner: Rc<usize>,
+    resets: usize,
+}
+
+#[test]
+fn projected_field() {
+    let old = Rc::new(1);
+    let inner = old.clone();
+    stack_pin_init!(let outer = pin_init!(Outer {
+        inner,
+        resets: 0,
+    }));
+    let this = outer.as_mut().project();
+    reinit(this.inner, Rc::new(2));
+    *this.resets += 1;
+    assert_eq!((*outer.inner, outer.resets), (2, 1));
+    assert_eq!(Rc::strong_count(&old), 1);
+}
@@ -0,0 +1,154 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+
+use core::cell::RefCell;
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+struct Logged<'a> {
+    log: &'a RefCell<Vec<&'static str>>,
+    name: &'static str,
+}
+
+impl Drop for Logged<'_> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name);
+    }
+}
+
+fn logged<'a>(
+    log: &'a RefCell<Vec<&'static str>>,
+    name: &'static str,
+    fail: bool,
+) -> impl Init<Logged<'a>, Error> {
+    if fail {
+        Err(Error)
+    } else {
+        Ok(Logged { log, name })
+    }
+}
+
+#[pin_data]
+struct Pair<'a> {
+    a: Logged<'a>,
+    b: Logged<'a>,
+}
+
+fn pair<'a>(log: &'a RefCell<Vec<&'static str>>, fail: bool) -> impl PinInit<Pair<'a>, Error> {
+    pin_init!(Pair {
+        _: {
+            log.borrow_mut().push("register 1");
+        } else {
+            log.borrow_mut().push("unregister 1");
+        },
+        a <- logged(log, "drop a", false),
+        _: {
+            log.borrow_mut().push("register 2");
+        } else {
+            log.borrow_mut().push("unregister 2");
+        },
+        b <- logged(log, "drop b", fail),
+    }? Error)
+}
+
+#[test]
+fn undo_in_reverse_order() {
+    let log = RefCell::new(vec![]);
+    stack_try_pin_init!(let res: Pair<'_> = pair(&log, true));
+    assert_eq!(res.err(), Some(Error));
+    assert_eq!(
+        *log.borrow(),
+        [
+            "register 1",
+            "register 2",
+            "unregister 2",
+            "drop a",
+            "unregister 1"
+        ]
+    );
+}
+
+#[test]
+fn success_does_not_undo() {
+    let log = RefCell::new(vec![]);
+    {
+        stack_try_pin_init!(let res: Pair<'_> = pair(&log, false));
+        assert!(res.is_ok());
+        assert_eq!(*log.borrow(), ["register 1", "register 2"]);
+    }
+    assert_eq!(
+        *log.borrow(),
+        ["register 1", "register 2", "drop a", "drop b"]
+    );
+}
+
+#[cfg(feature = "std")]
+fn panicking<'a>() -> impl Init<Logged<'a>, Error> {
+    // SAFETY: The slot is not touched.
+    unsafe { init_from_closure(|_| panic!("b panicked")) }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn undo_on_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let log = RefCell::new(vec![]);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let log = &log;
+        let init = pin_init!(Pair {
+            a <- logged(log, "drop a", false),
+            _: {} else {
+                log.borrow_mut().push("undo");
+            },
+            b <- panicking(),
+        }? Error);
+        stack_try_pin_init!(let _res: Pair<'_> = init);
+    }));
+    assert!(res.is_err());
+    assert_eq!(*log.borrow(), ["undo", "drop a"]);
+}
+
+#[test]
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn undo_in_init_and_nested() {
+    use core::convert::Infallible;
+
+    struct Inner {
+        x: u32,
+        y: u32,
+    }
+    struct Outer {
+        inner: Inner,
+        z: u32,
+    }
+
+    let log = RefCell::new(vec![]);
+    let events = &log;
+    let res = Box::<Outer>::new_uninit().write_init(init!(Outer {
+        inner: Inner {
+            x: 1,
+            _: {} else {
+                events.borrow_mut().push("undo inner");
+            },
+            y <- Err(Error),
+        },
+        z: 3,
+    }? Error));
+    assert_eq!(res.err(), Some(Error));
+    assert_eq!(*log.borrow(), ["undo inner"]);
+
+    let res = Box::<Outer>::new_uninit()
+        .write_init(init!(Outer {
+        _: {} else {
+            events.borrow_mut().push("undo outer");
+        },
+        inner: Inner { x: 1, y <- Ok::<_, Infallible>(2) },
+        z: 3,
+    }? Infallible))
+        .unwrap();
+    assert_eq!(res.inner.y + res.z, 5);
+    assert_eq!(*log.borrow(), ["undo inner"]);
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "std")]
+
+use core::mem::MaybeUninit;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use pin_init::*;
+
+#[test]
+fn drops_at_scope_end() {
+    let value = Rc::new(());
+    let mut slot = MaybeUninit::<Rc<()>>::uninit();
+    let res = scope_pin(&mut slot, Ok::<_, ()>(value.clone()), |v| {
+        assert_eq!(Rc::strong_count(&v), 2);
+        7
+    });
+    assert_eq!(res, Ok(7));
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn drops_on_panic() {
+    let value = Rc::new(());
+    let mut slot = MaybeUninit::<Rc<()>>::uninit();
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = scope_pin(&mut slot, Ok::<_, ()>(value.clone()), |_| panic!("boom"));
+    }));
+    assert!(res.is_err());
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn error_skips_closure() {
+    let mut slot = MaybeUninit::<u32>::uninit();
+    let res = scope_pin(&mut slot, Err(3), |_| unreachable!());
+    assert_eq!(res, Err::<(), _>(3));
+}
+
+#[test]
+fn slot_is_reusable() {
+    let value = Rc::new(());
+    let mut slot = MaybeUninit::<Rc<()>>::uninit();
+    for _ in 0..3 {
+        scope_pin(&mut slot, Ok::<_, ()>(value.clone()), |_| ()).unwrap();
+    }
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn init_guard() {
+    let value = Rc::new(());
+    let mut slot = MaybeUninit::<Rc<()>>::uninit();
+    let guard = InitGuard::new(&mut slot, Ok::<_, ()>(value.clone())).unwrap();
+    assert_eq!(Rc::strong_count(&guard), 2);
+    drop(guard);
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    let guard = InitGuard::new(&mut slot, Ok::<_, ()>(value.clone())).unwrap();
+    let inner = guard.into_inner();
+    assert_eq!(Rc::strong_count(&value), 2);
+    drop(inner);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn init_guard_error() {
+    let mut slot = MaybeUninit::<u32>::uninit();
+    assert_eq!(InitGuard::new(&mut slot, Err(1)).err(), Some(1));
+}
@@ -0,0 +1,88 @@
+use core::marker::PhantomPinned;
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+#[pin_data]
+struct Foo {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn fixed(value: u32) -> impl PinInit<Foo, Error> {
+    pin_init!(Foo {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+fn doubled(value: u32) -> impl PinInit<Foo, Error> {
+    pin_init!(Foo {
+        value: value * 2,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+fn failing(code: u32) -> impl PinInit<Foo, Error> {
+    Err(Error(code))
+}
+
+fn either(left: bool) -> impl PinInit<Foo, Error> {
+    if left {
+        EitherInit::Left(fixed(1))
+    } else {
+        EitherInit::Right(doubled(1))
+    }
+}
+
+#[test]
+fn either_init() {
+    stack_try_pin_init!(let left = either(true));
+    assert_eq!(left.unwrap().value, 1);
+    stack_try_pin_init!(let right = either(false));
+    assert_eq!(right.unwrap().value, 2);
+}
+
+fn select(mode: u32) -> impl PinInit<Foo, Error> {
+    select_init!(match mode {
+        0 => fixed(10),
+        1 => {
+            let value = mode + 10;
+            doubled(value)
+        }
+        2 | 3 => fixed(mode),
+        code => failing(code),
+    })
+}
+
+#[test]
+fn select_match() {
+    let values: Vec<_> = (0..5)
+        .map(|mode| {
+            stack_try_pin_init!(let foo = select(mode));
+            foo.map(|foo| foo.value)
+        })
+        .collect();
+    assert_eq!(values, [Ok(10), Ok(22), Ok(2), Ok(3), Err(Error(4))]);
+}
+
+fn select_if(value: i32) -> impl Init<i32, Error> {
+    select_init!(if value < 0 {
+        Err(Error(0))
+    } else if value == 0 {
+        Ok(1)
+    } else {
+        init_scope(move || Ok(Ok(value * 2)))
+    })
+}
+
+#[test]
+fn select_if_else() {
+    for (value, expected) in [(-1, Err(Error(0))), (0, Ok(1)), (3, Ok(6))] {
+        stack_try_pin_init!(let x: i32 = select_if(value));
+        assert_eq!(x.map(|x| *x), expected);
+    }
+}
@@ -0,0 +1,122 @@
+use core::{mem::MaybeUninit, pin::Pin};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    rc::Rc,
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+fn fill(rc: &Rc<()>, fail_at: usize) -> impl Init<[Rc<()>; 4], (usize, Error)> + '_ {
+    // SAFETY: On success, all elements have been initialized, on error, the builder drops the
+    // elements initialized so far.
+    unsafe {
+        init_from_closure(move |slot: *mut [Rc<()>; 4]| {
+            let mut builder = SliceBuilder::new(slot);
+            while !builder.is_full() {
+                if builder.len() == fail_at {
+                    builder.push_init(Err(Error))?;
+                }
+                builder.push_init(Ok(rc.clone()))?;
+            }
+            builder.finish();
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn complete() {
+    let rc = Rc::new(());
+    {
+        stack_try_pin_init!(let array = fill(&rc, usize::MAX));
+        assert_eq!(array.unwrap().len(), 4);
+        assert_eq!(Rc::strong_count(&rc), 5);
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn error_reports_index() {
+    let rc = Rc::new(());
+    stack_try_pin_init!(let array = fill(&rc, 2));
+    assert_eq!(array.err(), Some((2, Error)));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn drop_without_finish() {
+    let rc = Rc::new(());
+    let mut slot = MaybeUninit::<[Rc<()>; 3]>::uninit();
+    // SAFETY: `slot` is valid for writes, uninitialized and not moved while the builder exists.
+    let mut builder = unsafe { SliceBuilder::new(slot.as_mut_ptr()) };
+    builder.push_init(rc.clone()).unwrap();
+    assert_eq!((builder.len(), builder.capacity()), (1, 3));
+    drop(builder);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn panic_drops_prefix() {
+    let rc = Rc::new(());
+    let mut slot = MaybeUninit::<[Rc<()>; 3]>::uninit();
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: `slot` is valid for writes and uninitialized.
+        let mut builder = unsafe { SliceBuilder::new(slot.as_mut_ptr()) };
+        builder.push_init(rc.clone()).unwrap();
+        builder.push_init(rc.clone()).unwrap();
+        // SAFETY: The initializer panics before touching the slot.
+        let panicking = unsafe { init_from_closure(|_| -> Result<(), Error> { panic!("oops") }) };
+        let _ = builder.push_init(panicking);
+    }));
+    assert!(res.is_err());
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn finish_returns_slice() {
+    let mut slot = MaybeUninit::<[u8; 3]>::uninit();
+    // SAFETY: `slot` is valid for reads and writes, uninitialized and only accessed through the
+    // builder and the returned slice.
+    let mut builder = unsafe { SliceBuilder::new(slot.as_mut_ptr()) };
+    for i in 1..=3 {
+        builder.push_init(i).unwrap();
+    }
+    let values = builder.finish();
+    values[0] = 4;
+    assert_eq!(values, &[4, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "not all elements have been initialized")]
+fn finish_incomplete() {
+    let mut slot = MaybeUninit::<[u8; 2]>::uninit();
+    // SAFETY: `slot` is valid for writes and uninitialized.
+    let mut builder = unsafe { SliceBuilder::new(slot.as_mut_ptr()) };
+    builder.push_init(1).unwrap();
+    builder.finish();
+}
+
+#[test]
+#[should_panic(expected = "all elements have already been initialized")]
+fn push_when_full() {
+    let mut slot = MaybeUninit::<[u8; 1]>::uninit();
+    // SAFETY: `slot` is valid for writes and uninitialized.
+    let mut builder = unsafe { SliceBuilder::new(slot.as_mut_ptr()) };
+    builder.push_init(1).unwrap();
+    builder.push_init(2).unwrap();
+}
+
+#[test]
+fn pinned_finish() {
+    let mut slot = MaybeUninit::<[u8; 2]>::uninit();
+    // SAFETY: `slot` is valid for reads and writes, uninitialized, not moved and only accessed
+    // through the builder and the returned slice.
+    let mut builder = unsafe { PinSliceBuilder::new(slot.as_mut_ptr()) };
+    builder.push_pin_init(1).unwrap();
+    builder.push_init(2).unwrap();
+    let values: Pin<&mut [u8]> = builder.finish();
+    assert_eq!(&*values, &[1, 2]);
+}
@@ -0,0 +1,85 @@
+use core::{
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(usize);
+
+#[pin_data]
+struct Node {
+    value: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn node(value: usize) -> impl PinInit<Node> {
+    pin_init!(Node {
+        value,
+        _pin: PhantomPinned,
+    })
+}
+
+fn try_node(value: usize) -> impl PinInit<Node, Error> {
+    pin_init!(Node {
+        value,
+        _pin: PhantomPinned,
+    }? Error)
+}
+
+#[test]
+fn multiple_bindings() {
+    stack_pin_init!(let a = node(1), b: Node = node(a.value + 1), c = node(b.value + 1),);
+    let _: Pin<&mut Node> = c;
+    assert_eq!((a.value, b.value, c.value), (1, 2, 3));
+}
+
+#[test]
+fn array_binding() {
+    stack_pin_init!(let nodes: [Node; 4] = |i| node(i * 2), last = node(nodes[3].value + 1));
+    let mut nodes: Pin<&mut [Node; 4]> = nodes;
+    assert_eq!(last.value, 7);
+    assert_eq!(pin_slice_get_mut(nodes.as_mut(), 2).unwrap().value, 4);
+    assert!(pin_slice_get_mut(nodes.as_mut(), 4).is_none());
+    let values: Vec<_> = pin_slice_iter_mut(nodes).map(|n| n.value).collect();
+    assert_eq!(values, [0, 2, 4, 6]);
+}
+
+#[test]
+fn try_multiple_bindings() {
+    stack_try_pin_init!(let a = try_node(1), b: [Node; 2] = |i| try_node(i));
+    assert_eq!(a.unwrap().value, 1);
+    assert_eq!(b.unwrap()[1].value, 1);
+}
+
+#[test]
+fn try_propagates() {
+    struct CountDrop<'a>(&'a AtomicUsize);
+
+    impl Drop for CountDrop<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn run(drops: &AtomicUsize, fail: usize) -> Result<usize, Error> {
+        stack_try_pin_init!(
+            let first =? Ok::<_, Error>(CountDrop(drops)),
+            nodes: [Node; 3] =? |i| {
+                let res = if i == fail { Err(Error(i)) } else { Ok(()) };
+                try_node(i).pin_chain(move |_| res)
+            },
+        );
+        let _ = first;
+        Ok(nodes[2].value)
+    }
+
+    let drops = AtomicUsize::new(0);
+    assert_eq!(run(&drops, 3), Ok(2));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(run(&drops, 1), Err(Error(1)));
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
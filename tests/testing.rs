@@ -0,0 +1,143 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(feature = "testing")]
+
+use core::{marker::PhantomPinned, mem};
+use std::panic::{self, AssertUnwindSafe};
+
+use pin_init::{testing::*, *};
+
+#[test]
+fn fault_injector() {
+    let fail = FaultInjector::new(1, Fault::Error);
+    let init = init_array_from_fn::<_, 3, u32, InjectedFault>(|i| {
+        fail.wrap_init(Ok::<_, InjectedFault>(i as u32))
+    });
+    stack_try_pin_init!(let array = init);
+    assert_eq!(array.err(), Some(InjectedFault));
+    assert!(fail.triggered());
+    assert_eq!(fail.steps(), 2);
+
+    let fail = FaultInjector::new(0, Fault::Panic);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        stack_try_pin_init!(let _value: u32 = fail.wrap(Ok::<u32, InjectedFault>(1)));
+    }));
+    assert!(res.is_err());
+    assert!(fail.triggered());
+
+    let fail = FaultInjector::never();
+    stack_try_pin_init!(let value: u32 = fail.wrap(Ok::<u32, InjectedFault>(1)));
+    assert_eq!(*value.unwrap(), 1);
+    assert!(!fail.triggered());
+    assert_eq!(fail.steps(), 1);
+}
+
+#[test]
+fn fail_after_const() {
+    let fail = FailAfter::<2>::new(Fault::Error);
+    let init = init_array_from_fn::<_, 4, u32, InjectedFault>(|i| {
+        fail.wrap_init(Ok::<_, InjectedFault>(i as u32))
+    });
+    stack_try_pin_init!(let array = init);
+    assert_eq!(array.err(), Some(InjectedFault));
+    assert!(fail.triggered());
+    assert_eq!(fail.steps(), 3);
+
+    let fail = FailAfter::<0>::new(Fault::Panic);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        stack_try_pin_init!(let _value: u32 = fail.wrap(Ok::<u32, InjectedFault>(1)));
+    }));
+    assert!(res.is_err());
+    assert!(fail.into_injector().triggered());
+}
+
+#[test]
+fn drop_tracker() {
+    let tracker = DropTracker::new();
+    let values: Vec<_> = (0..3).map(|_| tracker.track()).collect();
+    assert_eq!(
+        values.iter().map(Tracked::id).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+    assert_eq!(tracker.live(), [0, 1, 2]);
+    let [a, b, c]: [Tracked; 3] = values.try_into().unwrap();
+    drop(c);
+    drop(a);
+    assert_eq!(tracker.drop_order(), [2, 0]);
+    assert_eq!(tracker.live(), [1]);
+    mem::forget(b);
+    assert_eq!(tracker.created(), 3);
+    tracker.assert_no_double_drop();
+    let res = panic::catch_unwind(|| tracker.assert_all_dropped());
+    assert!(res.is_err());
+}
+
+#[pin_data]
+struct Node {
+    a: Tracked,
+    #[pin]
+    b: Tracked,
+    children: [Tracked; 3],
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+#[test]
+fn pin_init_cleanup() {
+    let steps = assert_pin_init_cleanup(|fail: FaultInjector, tracker: DropTracker| {
+        pin_init!(Node {
+            a <- fail.wrap_init(Ok::<_, InjectedFault>(tracker.track())),
+            b <- fail.wrap(Ok::<_, InjectedFault>(tracker.track())),
+            children <- init_array_from_fn(|_| {
+                fail.wrap_init(Ok::<_, InjectedFault>(tracker.track()))
+            }),
+            _pin: PhantomPinned,
+        }? InjectedFault)
+    });
+    assert_eq!(steps, 5);
+}
+
+#[test]
+#[should_panic(expected = "leaked")]
+fn detects_leak() {
+    assert_init_cleanup(|fail: FaultInjector, tracker: DropTracker| {
+        let value = tracker.track();
+        let step = fail.wrap_init(Ok::<_, InjectedFault>(()));
+        // SAFETY: `slot` is only written to on success.
+        unsafe {
+            init_from_closure(move |slot: *mut Tracked| {
+                let mut unit = ();
+                match step.__init(&mut unit) {
+                    Ok(()) => {
+                        slot.write(value);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        mem::forget(value);
+                        Err(e)
+                    }
+                }
+            })
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "not injected")]
+fn propagates_other_panics() {
+    let mut runs = 0;
+    assert_init_cleanup(|fail: FaultInjector, _| {
+        // The runs are: counting the steps, an error at step 0 and 1, a panic at step 0 and 1.
+        runs += 1;
+        let bug = runs == 5;
+        let first = fail.wrap_init(Ok::<_, InjectedFault>(()));
+        let second = fail.wrap_init(Ok::<_, InjectedFault>(()));
+        // SAFETY: The unit slots are valid and `()` has no drop glue.
+        unsafe {
+            init_from_closure(move |slot: *mut ()| {
+                first.__init(slot)?;
+                assert!(!bug, "not injected");
+                second.__init(slot)
+            })
+        }
+    });
+}
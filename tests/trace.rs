@@ -0,0 +1,141 @@
+#![cfg(all(feature = "trace", feature = "std"))]
+
+use core::{cell::RefCell, convert::Infallible};
+
+use pin_init::{trace::*, *};
+
+#[derive(Debug, PartialEq)]
+struct Error;
+
+#[pin_data]
+struct Inner {
+    a: u32,
+    b: u32,
+}
+
+#[pin_data]
+struct Outer {
+    #[pin]
+    inner: Inner,
+    c: u32,
+}
+
+fn inner(fail: bool) -> impl PinInit<Inner, Error> {
+    pin_init!(Inner {
+        a: 1,
+        b <- if fail { Err(Error) } else { Ok(2) },
+    }? Error)
+}
+
+std::thread_local! {
+    static EVENTS: RefCell<Vec<(&'static str, Phase, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the events of the current thread, since the tests run in parallel.
+fn record(event: Event) {
+    EVENTS.with(|events| {
+        events
+            .borrow_mut()
+            .push((event.field, event.phase, event.depth))
+    });
+}
+
+fn take_events() -> Vec<(&'static str, Phase, usize)> {
+    EVENTS.with(|events| events.take())
+}
+
+#[test]
+fn success() {
+    set_hook(record);
+    take_events();
+    stack_try_pin_init!(let outer: Outer = pin_init!(Outer {
+        inner <- inner(false),
+        c: 3,
+    }? Error));
+    assert!(outer.is_ok());
+    assert_eq!(
+        take_events(),
+        [
+            ("inner", Phase::Start, 0),
+            ("a", Phase::Start, 1),
+            ("a", Phase::Success, 1),
+            ("b", Phase::Start, 1),
+            ("b", Phase::Success, 1),
+            ("inner", Phase::Success, 0),
+            ("c", Phase::Start, 0),
+            ("c", Phase::Success, 0),
+        ]
+    );
+}
+
+#[test]
+fn failure_and_rollback() {
+    set_hook(record);
+    take_events();
+    stack_try_pin_init!(let outer: Outer = pin_init!(Outer {
+        c: 3,
+        inner <- inner(true),
+    }? Error));
+    assert_eq!(outer.err(), Some(Error));
+    assert_eq!(
+        take_events(),
+        [
+            ("c", Phase::Start, 0),
+            ("c", Phase::Success, 0),
+            ("inner", Phase::Start, 0),
+            ("a", Phase::Start, 1),
+            ("a", Phase::Success, 1),
+            ("b", Phase::Start, 1),
+            ("b", Phase::Failure, 1),
+            ("a", Phase::Rollback, 1),
+            ("inner", Phase::Failure, 0),
+            ("c", Phase::Rollback, 0),
+        ]
+    );
+}
+
+#[test]
+fn nested_struct_syntax() {
+    set_hook(record);
+    take_events();
+    let outer = Box::init(init!(Outer {
+        inner: Inner { a: 1, b <- Ok::<_, Infallible>(2) },
+        c: 3,
+    }))
+    .unwrap();
+    assert_eq!(outer.inner.b, 2);
+    assert_eq!(
+        take_events(),
+        [
+            ("inner", Phase::Start, 0),
+            ("a", Phase::Start, 1),
+            ("a", Phase::Success, 1),
+            ("b", Phase::Start, 1),
+            ("b", Phase::Success, 1),
+            ("inner", Phase::Success, 0),
+            ("c", Phase::Start, 0),
+            ("c", Phase::Success, 0),
+        ]
+    );
+}
+
+#[test]
+fn type_name() {
+    fn check_type(event: Event) {
+        if event.field == "x" {
+            assert!(event.ty.ends_with("Point"), "{}", event.ty);
+        }
+        record(event);
+    }
+
+    #[pin_data]
+    struct Point {
+        x: u32,
+    }
+
+    set_hook(check_type);
+    take_events();
+    let point = Box::pin_init(pin_init!(Point { x: 1 }? Infallible)).unwrap();
+    assert_eq!(point.x, 1);
+    assert_eq!(take_events().len(), 2);
+}
@@ -0,0 +1,17 @@
+use pin_init::*;
+
+struct Foo {
+    a: usize,
+    b: usize,
+}
+
+fn main() {
+    let _foo = init!(Foo {
+        if true {
+            a: 0,
+            b: 1,
+        } else {
+            a: 2,
+        }
+    });
+}
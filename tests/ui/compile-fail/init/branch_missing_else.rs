@@ -0,0 +1,13 @@
+use pin_init::*;
+
+struct Foo {
+    a: usize,
+}
+
+fn main() {
+    let _foo = init!(Foo {
+        if true {
+            a: 0,
+        }
+    });
+}
@@ -0,0 +1,16 @@
+use pin_init::*;
+
+struct Foo {
+    a: usize,
+}
+
+fn main() {
+    let _foo = init!(Foo {
+        if true {
+            _: {} else {},
+            a: 0,
+        } else {
+            a: 1,
+        }
+    });
+}
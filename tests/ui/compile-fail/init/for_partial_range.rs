@@ -0,0 +1,13 @@
+use pin_init::*;
+
+struct Table {
+    rows: [u32; 4],
+}
+
+fn main() {
+    let _ = init!(Table {
+        for i in 0..3 {
+            rows[i] <- Ok::<_, ()>(0),
+        }
+    }? ());
+}
@@ -0,0 +1,18 @@
+use pin_init::*;
+
+#[pin_data]
+struct Inner {
+    a: usize,
+    b: usize,
+}
+
+#[pin_data]
+struct Foo {
+    #[pin]
+    inner: Inner,
+}
+
+fn main() {
+    let _foo = pin_init!(Foo { inner: Inner { a <- Ok(0) } }? ::std::convert::Infallible);
+    let _foo = init!(Foo { inner: Inner { a <- Ok(0) } }? ::std::convert::Infallible);
+}
@@ -0,0 +1,10 @@
+use pin_init::*;
+
+#[pin_data(builder)]
+struct Foo {
+    #[cfg(any())]
+    a: usize,
+    b: usize,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use pin_init::*;
+
+#[pin_data(builder)]
+struct Foo {
+    #[pin]
+    a: usize,
+    b: usize,
+}
+
+fn main() {
+    let _ = Foo::pin_init_builder::<core::convert::Infallible>()
+        .a(Ok(1))
+        .build();
+}
@@ -0,0 +1,13 @@
+use pin_init::*;
+
+#[pin_data(builder)]
+struct Foo {
+    a: usize,
+}
+
+fn main() {
+    let _ = Foo::pin_init_builder::<core::convert::Infallible>()
+        .a(Ok(1))
+        .a(Ok(2))
+        .build();
+}
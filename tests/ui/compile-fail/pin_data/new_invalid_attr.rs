@@ -0,0 +1,9 @@
+use pin_init::*;
+
+#[pin_data(new)]
+struct Foo {
+    #[new(skip)]
+    a: usize,
+}
+
+fn main() {}
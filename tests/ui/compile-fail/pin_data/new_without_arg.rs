@@ -0,0 +1,9 @@
+use pin_init::*;
+
+#[pin_data]
+struct Foo {
+    #[new(value)]
+    a: usize,
+}
+
+fn main() {}
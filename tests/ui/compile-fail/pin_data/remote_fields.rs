@@ -0,0 +1,17 @@
+use pin_init::*;
+
+mod remote {
+    pub struct Foo {
+        pub a: usize,
+        pub b: usize,
+        c: usize,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Foo)]
+struct Foo {
+    a: usize,
+    d: usize,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use pin_init::*;
+
+mod remote {
+    #[repr(C)]
+    pub struct Foo {
+        pub a: u8,
+        pub b: u32,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Foo)]
+#[repr(C)]
+struct Foo {
+    b: u32,
+    a: u8,
+}
+
+fn main() {
+    let _ = pin_init!(Foo { a: 1, b: 2 });
+}
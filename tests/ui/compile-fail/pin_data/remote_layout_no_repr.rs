@@ -0,0 +1,21 @@
+use pin_init::*;
+
+mod remote {
+    #[repr(C)]
+    pub struct Foo {
+        pub a: u8,
+        pub b: u32,
+        pub c: u8,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Foo)]
+struct Foo {
+    a: u8,
+    b: u32,
+    c: u8,
+}
+
+fn main() {
+    let _ = pin_init!(Foo { a: 1, b: 2, c: 3 });
+}
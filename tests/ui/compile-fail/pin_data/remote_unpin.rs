@@ -0,0 +1,16 @@
+use core::marker::PhantomPinned;
+use pin_init::*;
+
+mod remote {
+    pub struct Foo {
+        pub a: Box<core::marker::PhantomPinned>,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Foo)]
+struct Foo {
+    #[pin]
+    a: Box<PhantomPinned>,
+}
+
+fn main() {}
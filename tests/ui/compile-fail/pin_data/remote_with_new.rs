@@ -0,0 +1,14 @@
+use pin_init::*;
+
+mod remote {
+    pub struct Foo {
+        pub a: usize,
+    }
+}
+
+#[pin_data(new, unsafe remote = remote::Foo)]
+struct Foo {
+    a: usize,
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use pin_init::*;
+
+fn main() {
+    let _init = select_init!(if true { Ok::<u8, ()>(1) });
+}
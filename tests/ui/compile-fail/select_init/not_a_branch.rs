@@ -0,0 +1,5 @@
+use pin_init::*;
+
+fn main() {
+    let _init = select_init!(Ok::<u8, ()>(1));
+}
@@ -91,6 +91,15 @@ const _: () = {
         {
             f
         }
+        /// Returns the pointer that the field check of the initializer macros uses to check that
+        /// all fields of the annotated struct have been initialized.
+        #[inline(always)]
+        fn __field_check_slot(
+            self,
+            slot: *mut Foo<'a, 'b, T, SIZE>,
+        ) -> *mut Foo<'a, 'b, T, SIZE> {
+            slot
+        }
         /// # Safety
         ///
         /// - `slot` is valid and properly aligned.
@@ -0,0 +1,272 @@
+use pin_init::*;
+struct Foo {
+    a: usize,
+    b: usize,
+    c: usize,
+}
+/// Pin-projections of [`Foo`]
+#[allow(dead_code, non_snake_case)]
+#[doc(hidden)]
+struct FooProjection<'__pin> {
+    a: ::core::pin::Pin<&'__pin mut usize>,
+    b: &'__pin mut usize,
+    c: &'__pin mut usize,
+    ___pin_phantom_data: ::core::marker::PhantomData<&'__pin mut ()>,
+}
+impl Foo {
+    /// Pin-projects all fields of `Self`.
+    ///
+    /// These fields are structurally pinned:
+    /// - `a`
+    ///
+    /// These fields are **not** structurally pinned:
+    /// - `b`
+    /// - `c`
+    #[inline]
+    fn project<'__pin>(
+        self: ::core::pin::Pin<&'__pin mut Self>,
+    ) -> FooProjection<'__pin> {
+        let this = unsafe { ::core::pin::Pin::get_unchecked_mut(self) };
+        FooProjection {
+            a: unsafe { ::core::pin::Pin::new_unchecked(&mut this.a) },
+            b: &mut this.b,
+            c: &mut this.c,
+            ___pin_phantom_data: ::core::marker::PhantomData,
+        }
+    }
+}
+/// Builder of a pin-initializer for [`Foo`], created by [`Foo::pin_init_builder`].
+#[must_use]
+struct FooBuilder<__E, __F0, __F1, __F2> {
+    a: __F0,
+    b: __F1,
+    c: __F2,
+    ___pin_phantom_data: ::pin_init::__internal::PhantomInvariant<(__E, Foo)>,
+}
+impl Foo {
+    /** Creates a builder of a pin-initializer for [`Foo`].
+
+ The initializers of the fields are given to the setters of the same name, [`build`](FooBuilder::build) then returns the pin-initializer.*/
+    #[inline]
+    fn pin_init_builder<__E>() -> FooBuilder<
+        __E,
+        ::pin_init::__internal::Unset,
+        ::pin_init::__internal::Unset,
+        ::pin_init::__internal::Unset,
+    > {
+        FooBuilder {
+            a: ::pin_init::__internal::Unset,
+            b: ::pin_init::__internal::Unset,
+            c: ::pin_init::__internal::Unset,
+            ___pin_phantom_data: ::pin_init::__internal::PhantomInvariant::new(),
+        }
+    }
+}
+impl<__E, __F1, __F2> FooBuilder<__E, ::pin_init::__internal::Unset, __F1, __F2> {
+    /// Sets the initializer of the `a` field.
+    #[inline]
+    fn a<__I: ::pin_init::PinInit<usize, __E>>(
+        self,
+        a: __I,
+    ) -> FooBuilder<__E, __I, __F1, __F2> {
+        FooBuilder {
+            a: a,
+            b: self.b,
+            c: self.c,
+            ___pin_phantom_data: self.___pin_phantom_data,
+        }
+    }
+}
+impl<__E, __F0, __F2> FooBuilder<__E, __F0, ::pin_init::__internal::Unset, __F2> {
+    /// Sets the initializer of the `b` field.
+    #[inline]
+    fn b(self, b: usize) -> FooBuilder<__E, __F0, usize, __F2> {
+        FooBuilder {
+            b: b,
+            a: self.a,
+            c: self.c,
+            ___pin_phantom_data: self.___pin_phantom_data,
+        }
+    }
+}
+impl<__E, __F0, __F1> FooBuilder<__E, __F0, __F1, ::pin_init::__internal::Unset> {
+    /// Sets the initializer of the `c` field.
+    #[inline]
+    fn c<__I: ::pin_init::Init<usize, __E>>(
+        self,
+        c: __I,
+    ) -> FooBuilder<__E, __F0, __F1, ::pin_init::__internal::Set<__I>> {
+        FooBuilder {
+            c: ::pin_init::__internal::Set(c),
+            a: self.a,
+            b: self.b,
+            ___pin_phantom_data: self.___pin_phantom_data,
+        }
+    }
+}
+impl<__E, __F0, __F2> FooBuilder<__E, __F0, usize, __F2>
+where
+    __F0: ::pin_init::PinInit<usize, __E>,
+    __F2: ::pin_init::__internal::OptionalInit<usize, __E>,
+    <__F2 as ::pin_init::__internal::OptionalInit<
+        usize,
+        __E,
+    >>::Init: ::pin_init::Init<usize, __E>,
+{
+    /// Returns the pin-initializer, which initializes the fields in declaration order.
+    #[inline]
+    fn build(self) -> impl ::pin_init::PinInit<Foo, __E> {
+        let FooBuilder { a, b, c, .. } = self;
+        {
+            let __data = unsafe {
+                use ::pin_init::__internal::HasPinData;
+                Foo::__pin_data()
+            };
+            let init = __data
+                .__make_closure::<
+                    _,
+                    __E,
+                >(move |slot| {
+                    let __a_span = ::pin_init::__internal::FieldSpan::start(slot, "a");
+                    let mut __a_guard = (unsafe { __data.a(slot) }).init(a)?;
+                    __a_span.success(&mut __a_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let a = __a_guard.let_binding();
+                    let __b_span = ::pin_init::__internal::FieldSpan::start(slot, "b");
+                    let mut __b_guard = (unsafe { __data.b(slot) }).write(b);
+                    __b_span.success(&mut __b_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let b = __b_guard.let_binding();
+                    let __c_span = ::pin_init::__internal::FieldSpan::start(slot, "c");
+                    let mut __c_guard = (unsafe { __data.c(slot) })
+                        .init(c.or_else(|| ::core::default::Default::default()))?;
+                    __c_span.success(&mut __c_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let c = __c_guard.let_binding();
+                    ::core::mem::forget(__a_guard);
+                    ::core::mem::forget(__b_guard);
+                    ::core::mem::forget(__c_guard);
+                    #[allow(unreachable_code)]
+                    let _ = || unsafe {
+                        let _ = &(*slot).a;
+                        let _ = &(*slot).b;
+                        let _ = &(*slot).c;
+                        ::core::ptr::write(
+                            __data.__field_check_slot(slot),
+                            Foo {
+                                a: loop {},
+                                b: loop {},
+                                c: loop {},
+                            },
+                        )
+                    };
+                    Ok(unsafe { ::pin_init::__internal::InitOk::new() })
+                });
+            let init = move |slot| -> ::core::result::Result<(), __E> {
+                init(slot).map(|__InitOk| ())
+            };
+            unsafe { ::pin_init::pin_init_from_closure::<_, __E>(init) }
+        }
+    }
+}
+const _: () = {
+    #[doc(hidden)]
+    struct __ThePinData {
+        __phantom: ::pin_init::__internal::PhantomInvariant<Foo>,
+    }
+    impl ::core::clone::Clone for __ThePinData {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+    impl ::core::marker::Copy for __ThePinData {}
+    #[allow(dead_code)]
+    impl __ThePinData {
+        /// Type inference helper function.
+        #[inline(always)]
+        fn __make_closure<__F, __E>(self, f: __F) -> __F
+        where
+            __F: FnOnce(
+                *mut Foo,
+            ) -> ::core::result::Result<::pin_init::__internal::InitOk, __E>,
+        {
+            f
+        }
+        /// Returns the pointer that the field check of the initializer macros uses to check that
+        /// all fields of the annotated struct have been initialized.
+        #[inline(always)]
+        fn __field_check_slot(self, slot: *mut Foo) -> *mut Foo {
+            slot
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn a(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Pinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).a) }
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn b(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Unpinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).b) }
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn c(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Unpinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).c) }
+        }
+    }
+    unsafe impl ::pin_init::__internal::HasPinData for Foo {
+        type PinData = __ThePinData;
+        unsafe fn __pin_data() -> Self::PinData {
+            __ThePinData {
+                __phantom: ::pin_init::__internal::PhantomInvariant::new(),
+            }
+        }
+    }
+    #[allow(dead_code, non_snake_case)]
+    struct __Unpin<'__pin> {
+        __phantom_pin: ::pin_init::__internal::PhantomInvariantLifetime<'__pin>,
+        __phantom: ::pin_init::__internal::PhantomInvariant<Foo>,
+        a: usize,
+    }
+    #[doc(hidden)]
+    impl<'__pin> ::core::marker::Unpin for Foo
+    where
+        __Unpin<'__pin>: ::core::marker::Unpin,
+    {}
+    trait MustNotImplDrop {}
+    impl<T: ::core::ops::Drop + ?::core::marker::Sized> MustNotImplDrop for T {}
+    impl MustNotImplDrop for Foo {}
+    trait UselessPinnedDropImpl_you_need_to_specify_PinnedDrop {}
+    impl<
+        T: ::pin_init::PinnedDrop + ?::core::marker::Sized,
+    > UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for T {}
+    impl UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for Foo {}
+};
+fn main() {}
@@ -0,0 +1,197 @@
+use pin_init::*;
+struct Foo {
+    a: usize,
+    b: usize,
+    c: usize,
+}
+/// Pin-projections of [`Foo`]
+#[allow(dead_code, non_snake_case)]
+#[doc(hidden)]
+struct FooProjection<'__pin> {
+    a: ::core::pin::Pin<&'__pin mut usize>,
+    b: &'__pin mut usize,
+    c: &'__pin mut usize,
+    ___pin_phantom_data: ::core::marker::PhantomData<&'__pin mut ()>,
+}
+impl Foo {
+    /// Pin-projects all fields of `Self`.
+    ///
+    /// These fields are structurally pinned:
+    /// - `a`
+    ///
+    /// These fields are **not** structurally pinned:
+    /// - `b`
+    /// - `c`
+    #[inline]
+    fn project<'__pin>(
+        self: ::core::pin::Pin<&'__pin mut Self>,
+    ) -> FooProjection<'__pin> {
+        let this = unsafe { ::core::pin::Pin::get_unchecked_mut(self) };
+        FooProjection {
+            a: unsafe { ::core::pin::Pin::new_unchecked(&mut this.a) },
+            b: &mut this.b,
+            c: &mut this.c,
+            ___pin_phantom_data: ::core::marker::PhantomData,
+        }
+    }
+}
+impl Foo {
+    /// Creates a pin-initializer for [`Foo`] from its fields.
+    #[inline]
+    fn new<__E>(
+        a: impl ::pin_init::PinInit<usize, __E>,
+        b: usize,
+    ) -> impl ::pin_init::PinInit<Self, __E> {
+        {
+            let __data = unsafe {
+                use ::pin_init::__internal::HasPinData;
+                Self::__pin_data()
+            };
+            let init = __data
+                .__make_closure::<
+                    _,
+                    __E,
+                >(move |slot| {
+                    let __a_span = ::pin_init::__internal::FieldSpan::start(slot, "a");
+                    let mut __a_guard = (unsafe { __data.a(slot) }).init(a)?;
+                    __a_span.success(&mut __a_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let a = __a_guard.let_binding();
+                    let __b_span = ::pin_init::__internal::FieldSpan::start(slot, "b");
+                    let mut __b_guard = (unsafe { __data.b(slot) }).write(b);
+                    __b_span.success(&mut __b_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let b = __b_guard.let_binding();
+                    let __c_span = ::pin_init::__internal::FieldSpan::start(slot, "c");
+                    let mut __c_guard = (unsafe { __data.c(slot) })
+                        .write(::core::default::Default::default());
+                    __c_span.success(&mut __c_guard);
+                    #[allow(unused_variables, non_snake_case)]
+                    let c = __c_guard.let_binding();
+                    ::core::mem::forget(__a_guard);
+                    ::core::mem::forget(__b_guard);
+                    ::core::mem::forget(__c_guard);
+                    #[allow(unreachable_code)]
+                    let _ = || unsafe {
+                        let _ = &(*slot).a;
+                        let _ = &(*slot).b;
+                        let _ = &(*slot).c;
+                        ::core::ptr::write(
+                            __data.__field_check_slot(slot),
+                            Self {
+                                a: loop {},
+                                b: loop {},
+                                c: loop {},
+                            },
+                        )
+                    };
+                    Ok(unsafe { ::pin_init::__internal::InitOk::new() })
+                });
+            let init = move |slot| -> ::core::result::Result<(), __E> {
+                init(slot).map(|__InitOk| ())
+            };
+            unsafe { ::pin_init::pin_init_from_closure::<_, __E>(init) }
+        }
+    }
+}
+const _: () = {
+    #[doc(hidden)]
+    struct __ThePinData {
+        __phantom: ::pin_init::__internal::PhantomInvariant<Foo>,
+    }
+    impl ::core::clone::Clone for __ThePinData {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+    impl ::core::marker::Copy for __ThePinData {}
+    #[allow(dead_code)]
+    impl __ThePinData {
+        /// Type inference helper function.
+        #[inline(always)]
+        fn __make_closure<__F, __E>(self, f: __F) -> __F
+        where
+            __F: FnOnce(
+                *mut Foo,
+            ) -> ::core::result::Result<::pin_init::__internal::InitOk, __E>,
+        {
+            f
+        }
+        /// Returns the pointer that the field check of the initializer macros uses to check that
+        /// all fields of the annotated struct have been initialized.
+        #[inline(always)]
+        fn __field_check_slot(self, slot: *mut Foo) -> *mut Foo {
+            slot
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn a(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Pinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).a) }
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn b(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Unpinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).b) }
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn c(
+            self,
+            slot: *mut Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Unpinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).c) }
+        }
+    }
+    unsafe impl ::pin_init::__internal::HasPinData for Foo {
+        type PinData = __ThePinData;
+        unsafe fn __pin_data() -> Self::PinData {
+            __ThePinData {
+                __phantom: ::pin_init::__internal::PhantomInvariant::new(),
+            }
+        }
+    }
+    #[allow(dead_code, non_snake_case)]
+    struct __Unpin<'__pin> {
+        __phantom_pin: ::pin_init::__internal::PhantomInvariantLifetime<'__pin>,
+        __phantom: ::pin_init::__internal::PhantomInvariant<Foo>,
+        a: usize,
+    }
+    #[doc(hidden)]
+    impl<'__pin> ::core::marker::Unpin for Foo
+    where
+        __Unpin<'__pin>: ::core::marker::Unpin,
+    {}
+    trait MustNotImplDrop {}
+    impl<T: ::core::ops::Drop + ?::core::marker::Sized> MustNotImplDrop for T {}
+    impl MustNotImplDrop for Foo {}
+    trait UselessPinnedDropImpl_you_need_to_specify_PinnedDrop {}
+    impl<
+        T: ::pin_init::PinnedDrop + ?::core::marker::Sized,
+    > UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for T {}
+    impl UselessPinnedDropImpl_you_need_to_specify_PinnedDrop for Foo {}
+};
+fn main() {}
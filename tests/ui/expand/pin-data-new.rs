@@ -0,0 +1,13 @@
+use pin_init::*;
+
+#[pin_data(new)]
+struct Foo {
+    #[pin]
+    a: usize,
+    #[new(value)]
+    b: usize,
+    #[new(default)]
+    c: usize,
+}
+
+fn main() {}
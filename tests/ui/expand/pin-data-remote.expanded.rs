@@ -0,0 +1,143 @@
+use core::marker::PhantomPinned;
+use pin_init::*;
+mod remote {
+    pub struct Foo {
+        pub a: usize,
+        pub _pin: core::marker::PhantomPinned,
+    }
+}
+struct Foo {
+    a: usize,
+    _pin: PhantomPinned,
+}
+const _: () = {
+    #[doc(hidden)]
+    struct __ThePinData {
+        __phantom: ::pin_init::__internal::PhantomInvariant<Foo>,
+    }
+    impl ::core::clone::Clone for __ThePinData {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+    impl ::core::marker::Copy for __ThePinData {}
+    #[allow(dead_code)]
+    impl __ThePinData {
+        /// Type inference helper function.
+        #[inline(always)]
+        fn __make_closure<__F, __E>(self, f: __F) -> __F
+        where
+            __F: FnOnce(
+                *mut remote::Foo,
+            ) -> ::core::result::Result<::pin_init::__internal::InitOk, __E>,
+        {
+            f
+        }
+        /// Returns the pointer that the field check of the initializer macros uses to check that
+        /// all fields of the annotated struct have been initialized.
+        #[inline(always)]
+        fn __field_check_slot(self, slot: *mut remote::Foo) -> *mut Foo {
+            slot as *mut Foo
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn a(
+            self,
+            slot: *mut remote::Foo,
+        ) -> ::pin_init::__internal::Slot<::pin_init::__internal::Unpinned, usize> {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot).a) }
+        }
+        /// # Safety
+        ///
+        /// - `slot` is valid and properly aligned.
+        /// - `(*slot).#field_name` is properly aligned.
+        /// - `(*slot).#field_name` points to uninitialized and exclusively accessed
+        ///   memory.
+        #[allow(non_snake_case)]
+        #[inline(always)]
+        unsafe fn _pin(
+            self,
+            slot: *mut remote::Foo,
+        ) -> ::pin_init::__internal::Slot<
+            ::pin_init::__internal::Pinned,
+            PhantomPinned,
+        > {
+            unsafe { ::pin_init::__internal::Slot::new(&raw mut (*slot)._pin) }
+        }
+    }
+    unsafe impl ::pin_init::__internal::HasPinData for Foo {
+        type PinData = __ThePinData;
+        unsafe fn __pin_data() -> Self::PinData {
+            let () = Self::__CHECK_REMOTE_LAYOUT;
+            __ThePinData {
+                __phantom: ::pin_init::__internal::PhantomInvariant::new(),
+            }
+        }
+    }
+    #[allow(dead_code, non_snake_case)]
+    fn __check_remote_fields(remote: remote::Foo) -> Foo {
+        let remote::Foo { a, _pin } = remote;
+        Foo { a, _pin }
+    }
+    #[allow(dead_code, non_snake_case)]
+    fn __check_mirror_fields(mirror: Foo) -> remote::Foo {
+        let Foo { a, _pin } = mirror;
+        remote::Foo { a, _pin }
+    }
+    trait RemoteWithPinnedFieldsMustNotBeUnpin<A> {
+        fn check() {}
+    }
+    impl<T: ?::core::marker::Sized> RemoteWithPinnedFieldsMustNotBeUnpin<()> for T {}
+    impl<
+        T: ?::core::marker::Sized + ::core::marker::Unpin,
+    > RemoteWithPinnedFieldsMustNotBeUnpin<u8> for T {}
+    #[allow(dead_code)]
+    fn __check_remote_unpin() {
+        let _ = <remote::Foo as RemoteWithPinnedFieldsMustNotBeUnpin<_>>::check;
+    }
+    trait MustNotImplDrop {}
+    impl<T: ::core::ops::Drop + ?::core::marker::Sized> MustNotImplDrop for T {}
+    impl MustNotImplDrop for remote::Foo {}
+    impl Foo {
+        const __CHECK_REMOTE_LAYOUT: () = {
+            if !(const { builtin # offset_of(remote::Foo, a) }
+                == const { builtin # offset_of(Self, a) })
+            {
+                {
+                    ::core::panicking::panic_fmt(
+                        format_args!(
+                            "the field `a` of the remote struct has a different offset",
+                        ),
+                    );
+                }
+            }
+            if !(const { builtin # offset_of(remote::Foo, _pin) }
+                == const { builtin # offset_of(Self, _pin) })
+            {
+                {
+                    ::core::panicking::panic_fmt(
+                        format_args!(
+                            "the field `_pin` of the remote struct has a different offset",
+                        ),
+                    );
+                }
+            }
+            if !(::core::mem::size_of::<remote::Foo>() == ::core::mem::size_of::<Self>())
+            {
+                {
+                    ::core::panicking::panic_fmt(
+                        format_args!("the remote struct has a different size"),
+                    );
+                }
+            }
+        };
+    }
+    const _: () = Foo::__CHECK_REMOTE_LAYOUT;
+};
+fn main() {}
@@ -0,0 +1,18 @@
+use core::marker::PhantomPinned;
+use pin_init::*;
+
+mod remote {
+    pub struct Foo {
+        pub a: usize,
+        pub _pin: core::marker::PhantomPinned,
+    }
+}
+
+#[pin_data(unsafe remote = remote::Foo)]
+struct Foo {
+    a: usize,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+fn main() {}
@@ -55,6 +55,12 @@ const _: () = {
         {
             f
         }
+        /// Returns the pointer that the field check of the initializer macros uses to check that
+        /// all fields of the annotated struct have been initialized.
+        #[inline(always)]
+        fn __field_check_slot(self, slot: *mut Foo) -> *mut Foo {
+            slot
+        }
         /// # Safety
         ///
         /// - `slot` is valid and properly aligned.
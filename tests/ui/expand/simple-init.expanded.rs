@@ -12,7 +12,9 @@ fn main() {
                 ::core::convert::Infallible,
             >(move |slot| {
                 #[allow(unreachable_code)]
-                let _ = || unsafe { ::core::ptr::write(slot, Foo {}) };
+                let _ = || unsafe {
+                    ::core::ptr::write(__data.__field_check_slot(slot), Foo {})
+                };
                 Ok(unsafe { ::pin_init::__internal::InitOk::new() })
             });
         let init = move |
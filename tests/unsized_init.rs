@@ -0,0 +1,68 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(feature = "dst")]
+
+use core::{alloc::AllocError, marker::PhantomPinned, pin::Pin};
+use std::sync::Arc;
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(u32);
+
+impl From<AllocError> for Error {
+    fn from(_: AllocError) -> Self {
+        Self(u32::MAX)
+    }
+}
+
+trait Driver {
+    fn id(self: Pin<&Self>) -> u32;
+}
+
+#[pin_data]
+struct Foo {
+    value: u32,
+    #[pin]
+    _pin: PhantomPinned,
+}
+
+impl Driver for Foo {
+    fn id(self: Pin<&Self>) -> u32 {
+        self.value
+    }
+}
+
+fn probe<D: Driver + 'static>(init: impl PinInit<D, Error>) -> Result<Pin<Box<dyn Driver>>, Error> {
+    Box::<dyn Driver>::try_pin_init_unsized(init)
+}
+
+#[test]
+fn box_dyn() {
+    let driver = probe(pin_init!(Foo {
+        value: 3,
+        _pin: PhantomPinned,
+    }? Error));
+    assert_eq!(driver.unwrap().as_ref().id(), 3);
+    let failed = probe::<Foo>(Err(Error(1)));
+    assert_eq!(failed.err(), Some(Error(1)));
+}
+
+#[test]
+fn arc_dyn() {
+    let driver = Arc::<dyn Driver + Send + Sync>::pin_init_unsized(pin_init!(Foo {
+        value: 5,
+        _pin: PhantomPinned,
+    }))
+    .unwrap();
+    let other = driver.clone();
+    assert_eq!(other.as_ref().id(), 5);
+}
+
+#[test]
+fn box_slice() {
+    let values =
+        Box::<[u32]>::init_unsized(init_array_from_fn::<_, 4, _, _>(|i| i as u32)).unwrap();
+    assert_eq!(&*values, [0, 1, 2, 3]);
+    let failed = Box::<[u32]>::try_init_unsized::<[u32; 2], _>(Err(Error(2)));
+    assert_eq!(failed.err(), Some(Error(2)));
+}
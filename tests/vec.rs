@@ -0,0 +1,83 @@
+#![cfg_attr(feature = "alloc", feature(allocator_api))]
+#![cfg(any(feature = "std", feature = "alloc"))]
+
+use std::{rc::Rc, thread};
+
+use pin_init::*;
+
+#[derive(Debug, PartialEq)]
+struct Error(usize);
+
+#[cfg(feature = "alloc")]
+impl From<core::alloc::AllocError> for Error {
+    fn from(_: core::alloc::AllocError) -> Self {
+        Self(usize::MAX)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl From<std::collections::TryReserveError> for Error {
+    fn from(_: std::collections::TryReserveError) -> Self {
+        Self(usize::MAX)
+    }
+}
+
+struct Record {
+    id: usize,
+    payload: [u8; 1024 * 1024],
+}
+
+fn record(id: usize) -> impl Init<Record> {
+    init!(Record {
+        id,
+        payload <- init_zeroed(),
+    })
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn big_records() {
+    // Any copy of a record on the stack overflows it.
+    thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(|| {
+            let mut queue = Vec::new();
+            queue.push_init(record(0)).unwrap();
+            queue.extend_init(4, |i| record(i + 1)).unwrap();
+            assert_eq!(queue.len(), 5);
+            assert!(queue.iter().enumerate().all(|(i, r)| r.id == i));
+            assert!(queue[4].payload.iter().all(|b| *b == 0));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn push_failure() {
+    let rc = Rc::new(());
+    let mut vec = vec![rc.clone()];
+    assert_eq!(vec.try_push_init(Err(Error(1))), Err(Error(1)));
+    assert_eq!(vec.len(), 1);
+    assert_eq!(Rc::strong_count(&rc), 2);
+}
+
+#[test]
+fn extend_failure() {
+    let rc = Rc::new(());
+    let mut vec = vec![rc.clone()];
+    let res = vec.try_extend_init(5, |i| if i < 3 { Ok(rc.clone()) } else { Err(Error(i)) });
+    assert_eq!(res, Err(Error(3)));
+    assert_eq!(vec.len(), 1);
+    assert_eq!(Rc::strong_count(&rc), 2);
+    drop(vec);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn reserve_failure() {
+    let mut vec = vec![0u64];
+    let res = vec.try_extend_init(usize::MAX / 2, |_| Ok::<_, Error>(0));
+    assert_eq!(res, Err(Error(usize::MAX)));
+    assert_eq!(vec, [0]);
+}